@@ -0,0 +1,43 @@
+use cosmwasm_std::{Addr, Storage};
+
+use crate::state::{PauseCategory, Role, State, PAUSE_FLAGS, ROLES, STATE};
+use crate::ContractError;
+
+/// Loads the contract state, failing with `Unauthorized` unless `sender` is an owner.
+pub fn assert_owner(storage: &dyn Storage, sender: &Addr) -> Result<State, ContractError> {
+    let state = STATE.load(storage)?;
+    if !state.owners.contains(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(state)
+}
+
+/// Loads the contract state, failing with `Unauthorized` unless `sender` is an owner
+/// or has been granted `role`.
+pub fn assert_role(
+    storage: &dyn Storage,
+    sender: &Addr,
+    role: Role,
+) -> Result<State, ContractError> {
+    let state = STATE.load(storage)?;
+    if state.owners.contains(sender) {
+        return Ok(state);
+    }
+    let roles = ROLES.may_load(storage, sender.clone())?.unwrap_or_default();
+    if !roles.contains(&role) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(state)
+}
+
+/// Fails with `Paused` if the whole contract or any of `categories` is paused.
+pub fn assert_not_paused(
+    storage: &dyn Storage,
+    categories: &[PauseCategory],
+) -> Result<(), ContractError> {
+    let pause_flags = PAUSE_FLAGS.may_load(storage)?.unwrap_or_default();
+    if categories.iter().any(|x| pause_flags.is_paused(*x)) {
+        return Err(ContractError::Paused {});
+    }
+    Ok(())
+}
@@ -1,15 +1,26 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_json, to_json_binary, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Reply,
-    Response, StdResult, SubMsgResponse, SubMsgResult,
+    from_json, to_json_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, SubMsgResponse,
+    SubMsgResult, Uint128,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::{Bound, Map};
 
+use crate::auth::{assert_not_paused, assert_owner, assert_role};
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, PalomaMsg, QueryMsg, SendTx};
+use crate::msg::{
+    AdminAction, Asset, AssetInfo, CallbackMsg, Delivery, ExecuteMsg, ExternalQueryMsg,
+    IncentivizerQueryMsg, InstantiateMsg, LpBalanceInfo, LpTotalInfo, MigrateMsg, PairInfo,
+    PalomaMsg, ProposalInfo, QueryMsg, ReconcileResponse, RoleInfo, RolesResponse, SendTx,
+    SimulateAddLiquidityResponse, SimulateExchangeResponse, SimulateSwapOperationsResponse,
+    SimulationResponse, SwapOperation, SwapSimulation,
+};
 use crate::state::{
-    IncentivesSetting, State, CHAIN_SETTINGS, INCENTIVES_SETTING, LP_BALANCES, STATE,
+    IncentivesSetting, MultisigSetting, PauseCategory, Proposal, Role, State, CHAIN_SETTINGS,
+    INCENTIVES_SETTING, LP_BALANCES, LP_TOTALS, MULTISIG_SETTING, PAUSE_FLAGS, PENDING_OWNERS,
+    PRICE_GUARDS, PROPOSALS, PROPOSAL_COUNT, ROLES, STATE,
 };
 
 // version info for migration info
@@ -21,6 +32,36 @@ const EXECUTE_REPLY_ID: u64 = 2;
 const ADD_LIQUIDITY_REPLY_ID: u64 = 3;
 const EXECUTE_FOR_SINGLE_LIQUIDITY_REPLY_ID: u64 = 4;
 const CHECK_PADEX_DIFF_REPLY_ID: u64 = 5;
+const ZAP_OUT_REPLY_ID: u64 = 6;
+
+// the contract must always keep at least this many owners
+const MIN_OWNERS: usize = 1;
+
+// pagination limits for list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// decimals of the prices pairs accumulate in `CumulativePrices`
+const TWAP_PRECISION: u32 = 6;
+
+// decimals assumed for native denoms without bank metadata
+const DEFAULT_PRECISION: u32 = 6;
+
+fn assert_multisig_setting(
+    state: &State,
+    multisig_setting: &MultisigSetting,
+) -> Result<(), ContractError> {
+    if multisig_setting.approval_threshold == 0
+        || multisig_setting.approval_threshold > state.owners.len() as u64
+    {
+        return Err(ContractError::InvalidThreshold {});
+    }
+    // a zero TTL would expire every proposal the moment it is opened
+    if multisig_setting.proposal_ttl == 0 {
+        return Err(ContractError::InvalidProposalTtl {});
+    }
+    Ok(())
+}
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
@@ -31,6 +72,23 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
         vepades: msg.vepades,
     };
     INCENTIVES_SETTING.save(deps.storage, &incentives_setting)?;
+    let multisig_setting = MultisigSetting {
+        approval_threshold: msg.approval_threshold,
+        proposal_ttl: msg.proposal_ttl,
+    };
+    assert_multisig_setting(&STATE.load(deps.storage)?, &multisig_setting)?;
+    MULTISIG_SETTING.save(deps.storage, &multisig_setting)?;
+    // rebuild the holder index and the per-token totals over the existing ledger entries
+    let lp_balances = Map::<(String, String), Uint128>::new("lp_balances")
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    LP_TOTALS.clear(deps.storage);
+    for (key, balance) in lp_balances {
+        LP_BALANCES.save(deps.storage, key.clone(), &balance)?;
+        LP_TOTALS.update(deps.storage, key.1, |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default() + balance)
+        })?;
+    }
     Ok(Response::new().add_attribute("action", "migrate"))
 }
 
@@ -45,10 +103,18 @@ pub fn instantiate(
         owners: msg
             .owners
             .iter()
-            .map(|x| deps.api.addr_validate(x).unwrap())
-            .collect(),
+            .map(|x| deps.api.addr_validate(x))
+            .collect::<StdResult<_>>()?,
         retry_delay: msg.retry_delay,
     };
+    if state.owners.len() < MIN_OWNERS {
+        return Err(ContractError::MinimumOwners {});
+    }
+    let multisig_setting = MultisigSetting {
+        approval_threshold: msg.approval_threshold,
+        proposal_ttl: msg.proposal_ttl,
+    };
+    assert_multisig_setting(&state, &multisig_setting)?;
     let incentives_setting = IncentivesSetting {
         incentivizer: deps.api.addr_validate(msg.incentivizer.as_str())?,
         padex: msg.padex,
@@ -57,6 +123,7 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
     INCENTIVES_SETTING.save(deps.storage, &incentives_setting)?;
+    MULTISIG_SETTING.save(deps.storage, &multisig_setting)?;
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
@@ -74,8 +141,11 @@ pub fn execute(
             minimum_receive,
             max_spread,
             funds,
+            offer_amount,
             chain_id,
             recipient,
+            refund_on_failure,
+            delivery,
         } => execute::exchange(
             deps,
             env,
@@ -85,8 +155,11 @@ pub fn execute(
             minimum_receive,
             max_spread,
             funds,
+            offer_amount,
             chain_id,
             recipient,
+            refund_on_failure,
+            delivery,
         ),
         ExecuteMsg::SendToEVM {
             chain_id,
@@ -131,32 +204,92 @@ pub fn execute(
             lp_token,
             user,
             amount,
-        } => execute::add_lp_token(deps, info, lp_token, user, amount),
+        } => execute::add_lp_token(deps, env, info, lp_token, user, amount),
         ExecuteMsg::SetChainSetting {
             chain_id,
             compass_job_id,
             main_job_id,
         } => execute::set_chain_setting(deps, info, chain_id, compass_job_id, main_job_id),
-        ExecuteMsg::SetPaloma { chain_id } => execute::set_paloma(deps, info, chain_id),
+        ExecuteMsg::SetPaloma { chain_id } => {
+            execute::propose_admin_action(deps, env, info, AdminAction::SetPaloma { chain_id })
+        }
         ExecuteMsg::UpdateRefundWallet {
             chain_id,
             new_refund_wallet,
-        } => execute::update_refund_wallet(deps, info, chain_id, new_refund_wallet),
+        } => execute::propose_admin_action(
+            deps,
+            env,
+            info,
+            AdminAction::UpdateRefundWallet {
+                chain_id,
+                new_refund_wallet,
+            },
+        ),
         ExecuteMsg::UpdateGasFee {
             chain_id,
             new_gas_fee,
-        } => execute::update_gas_fee(deps, info, chain_id, new_gas_fee),
+        } => execute::propose_admin_action(
+            deps,
+            env,
+            info,
+            AdminAction::UpdateGasFee {
+                chain_id,
+                new_gas_fee,
+            },
+        ),
         ExecuteMsg::UpdateServiceFeeCollector {
             chain_id,
             new_service_fee_collector,
-        } => execute::update_service_fee_collector(deps, info, chain_id, new_service_fee_collector),
+        } => execute::propose_admin_action(
+            deps,
+            env,
+            info,
+            AdminAction::UpdateServiceFeeCollector {
+                chain_id,
+                new_service_fee_collector,
+            },
+        ),
         ExecuteMsg::UpdateServiceFee {
             chain_id,
             new_service_fee,
-        } => execute::update_service_fee(deps, info, chain_id, new_service_fee),
-        ExecuteMsg::UpdateConfig { retry_delay } => execute::update_config(deps, info, retry_delay),
-        ExecuteMsg::AddOwner { owners } => execute::add_owner(deps, info, owners),
-        ExecuteMsg::RemoveOwner { owner } => execute::remove_owner(deps, info, owner),
+        } => execute::propose_admin_action(
+            deps,
+            env,
+            info,
+            AdminAction::UpdateServiceFee {
+                chain_id,
+                new_service_fee,
+            },
+        ),
+        ExecuteMsg::ApproveProposal { proposal_id } => {
+            execute::approve_proposal(deps, env, info, proposal_id)
+        }
+        ExecuteMsg::UpdateConfig {
+            retry_delay,
+            proposal_ttl,
+        } => execute::update_config(deps, info, retry_delay, proposal_ttl),
+        ExecuteMsg::UpdateApprovalThreshold { approval_threshold } => {
+            execute::propose_admin_action(
+                deps,
+                env,
+                info,
+                AdminAction::UpdateApprovalThreshold { approval_threshold },
+            )
+        }
+        ExecuteMsg::ProposeOwner { owners } => {
+            execute::propose_admin_action(deps, env, info, AdminAction::ProposeOwner { owners })
+        }
+        ExecuteMsg::AcceptOwnership {} => execute::accept_ownership(deps, info),
+        ExecuteMsg::CancelOwnerProposal { owner } => {
+            execute::cancel_owner_proposal(deps, info, owner)
+        }
+        ExecuteMsg::RemoveOwner { owner } => {
+            execute::propose_admin_action(deps, env, info, AdminAction::RemoveOwner { owner })
+        }
+        ExecuteMsg::GrantRole { role, address } => execute::grant_role(deps, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => execute::revoke_role(deps, info, role, address),
+        ExecuteMsg::Pause { category } => execute::set_paused(deps, info, category, true),
+        ExecuteMsg::Unpause { category } => execute::set_paused(deps, info, category, false),
         ExecuteMsg::SendToken {
             chain_id,
             tokens,
@@ -169,40 +302,94 @@ pub fn execute(
             coins,
             slippage_tolerance,
             depositor,
-        } => execute::add_liquidity(deps, env, info, pair, coins, slippage_tolerance, depositor),
+            refund_on_failure,
+            min_lp_out,
+        } => execute::add_liquidity(
+            deps,
+            env,
+            info,
+            pair,
+            coins,
+            slippage_tolerance,
+            depositor,
+            refund_on_failure,
+            min_lp_out,
+        ),
         ExecuteMsg::RemoveLiquidity {
             chain_id,
             pair,
             amount,
             receiver,
-        } => execute::remove_liquidity(deps, env, info, chain_id, pair, amount, receiver),
+            min_assets_out,
+            receive_asset,
+            delivery,
+        } => execute::remove_liquidity(
+            deps,
+            env,
+            info,
+            chain_id,
+            pair,
+            amount,
+            receiver,
+            min_assets_out,
+            receive_asset,
+            delivery,
+        ),
         ExecuteMsg::CancelTx { transaction_id } => {
             execute::cancel_tx(deps, env, info, transaction_id)
         }
+        ExecuteMsg::Callback(CallbackMsg::ContinueRoute {
+            dex_router,
+            operations,
+            prev_balance,
+            hop_minimum,
+            minimum_receive,
+            max_spread,
+        }) => execute::continue_route(
+            deps,
+            env,
+            info,
+            dex_router,
+            operations,
+            prev_balance,
+            hop_minimum,
+            minimum_receive,
+            max_spread,
+        ),
+        ExecuteMsg::SetPriceGuard { pair, guard } => {
+            execute::set_price_guard(deps, info, pair, guard)
+        }
     }
 }
 
 pub mod execute {
     use std::collections::BTreeMap;
 
-    use cosmwasm_std::{Addr, Decimal, Decimal256, ReplyOn, SubMsg, Uint128, Uint256, WasmMsg};
+    use cosmwasm_std::{
+        Addr, Decimal, Decimal256, Empty, ReplyOn, StdError, SubMsg, Uint128, Uint256, WasmMsg,
+    };
     use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
     use ethabi::{Address, Contract, Function, Param, ParamType, StateMutability, Token, Uint};
 
     use super::*;
     use crate::{
         msg::{
-            Asset, AssetInfo, CancelTx, ConfigResponse, Cw20Msg, ExecuteJob, ExternalExecuteMsg,
-            ExternalQueryMsg, FeeInfoResponse, IncentivizerExecuteMsg, PairInfo, PairType,
-            PoolResponse, SwapOperation, VePadexExecuteMsg,
+            Asset, AssetInfo, CancelTx, ConfigResponse, CumulativePricesResponse, Cw20Msg,
+            ExecuteJob, ExternalExecuteMsg, ExternalQueryMsg, FeeInfoResponse,
+            IncentivizerExecuteMsg, PairInfo, PairType, PoolResponse, StablePoolConfig,
+            SwapOperation, VePadexExecuteMsg,
+        },
+        state::{
+            credit_balance, debit_balance, update_lp_balance, ChainSetting, PriceGuard,
+            PriceObservation, CHAIN_SETTINGS, GUARDED_PAIRS, LP_BALANCES, MESSAGE_TIMESTAMP,
+            PRICE_OBSERVATIONS,
         },
-        state::{ChainSetting, CHAIN_SETTINGS, LP_BALANCES, MESSAGE_TIMESTAMP},
     };
     use std::str::FromStr;
 
     #[allow(clippy::too_many_arguments)]
     pub fn exchange(
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         dex_router: Addr,
@@ -210,48 +397,358 @@ pub mod execute {
         minimum_receive: Option<Uint128>,
         max_spread: Option<Decimal>,
         funds: Vec<Coin>,
+        offer_amount: Option<Uint128>,
         chain_id: String,
         recipient: String,
+        refund_on_failure: bool,
+        delivery: Delivery,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::Swaps])?;
+        check_delivery(deps.as_ref(), &delivery)?;
 
-        let coin: Coin;
-
-        let SwapOperation::AstroSwap { ask_asset_info, .. } = operations.last().unwrap();
+        validate_route(&operations)?;
+        let offer_asset_info = operations[0].offer_asset_info();
+        let AssetInfo::NativeToken { denom } = &offer_asset_info else {
+            return Err(ContractError::UnsupportedCw20 {});
+        };
+        let offer_coin = match (funds.as_slice(), offer_amount) {
+            ([], Some(amount)) => Coin::new(amount, denom),
+            ([coin], None) if coin.denom == *denom => coin.clone(),
+            _ => return Err(ContractError::FundsMismatch {}),
+        };
+        if offer_coin.amount.is_zero() {
+            return Err(ContractError::FundsMismatch {});
+        }
+        // Every offer is paid from the recipient's deposit, never from other users' holdings.
+        debit_balance(deps.storage, &recipient, denom, offer_coin.amount)?;
 
-        if let AssetInfo::NativeToken { denom } = ask_asset_info {
-            coin = deps
-                .querier
-                .query_balance(env.contract.address.clone(), denom.clone())?;
+        let ask_asset_info = operations.last().unwrap().ask_asset_info();
+        // The offered coin leaves before the output arrives on circular routes.
+        let offered = if ask_asset_info == offer_asset_info {
+            offer_coin.amount
         } else {
-            return Err(ContractError::UnsupportedCw20 {});
+            Uint128::zero()
+        };
+        let asset = Asset {
+            amount: asset_balance(deps.as_ref(), &env, &ask_asset_info)?.checked_sub(offered)?,
+            info: ask_asset_info,
+        };
+        let refund = refund_on_failure.then(|| offer_coin.clone());
+
+        let pair_hops = operations
+            .iter()
+            .any(|x| matches!(x, SwapOperation::PairSwap { .. }));
+        // A router-only route is floored as a whole, each hop's floor being the next one's
+        // offer. A hop without a price breaks the chain, and the route is then walked hop by
+        // hop so its guarded hops are still floored.
+        let mut floor = None;
+        let mut walk = pair_hops;
+        if !pair_hops && minimum_receive.is_none() {
+            floor = Some(offer_coin.amount);
+            for operation in &operations {
+                if let Some(amount) = floor {
+                    floor = hop_floor(deps.branch(), operation, amount)?;
+                }
+                walk |= guarded_pair(deps.as_ref(), operation)?.is_some();
+            }
+            if floor.is_some() {
+                walk = false;
+            }
         }
+        let payload = to_json_binary(&(recipient, chain_id, asset, refund, delivery, floor))?;
 
-        let payload = to_json_binary(&(recipient, chain_id, coin))?;
+        let msg = if walk {
+            // The router cannot run pair hops, so walk the route through our own callbacks.
+            let balance = asset_balance(deps.as_ref(), &env, &offer_asset_info)?;
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::Callback(CallbackMsg::ContinueRoute {
+                    dex_router,
+                    operations,
+                    prev_balance: Asset {
+                        info: offer_asset_info,
+                        amount: balance.checked_sub(offer_coin.amount)?,
+                    },
+                    hop_minimum: None,
+                    minimum_receive,
+                    max_spread,
+                }))?,
+                funds: vec![],
+            }
+        } else {
+            WasmMsg::Execute {
+                contract_addr: dex_router.to_string(),
+                msg: to_json_binary(&ExternalExecuteMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive,
+                    to: None,
+                    max_spread,
+                })?,
+                funds: vec![offer_coin],
+            }
+        };
         Ok(Response::new()
             .add_submessage(SubMsg {
                 id: EXECUTE_REPLY_ID,
-                msg: CosmosMsg::Wasm(WasmMsg::Execute {
-                    contract_addr: dex_router.to_string(),
-                    msg: to_json_binary(&ExternalExecuteMsg::ExecuteSwapOperations {
-                        operations,
-                        minimum_receive,
-                        to: None,
-                        max_spread,
-                    })?,
-                    funds,
-                }),
+                msg: CosmosMsg::Wasm(msg),
                 payload,
                 gas_limit: None,
-                reply_on: ReplyOn::Success,
+                reply_on: reply_on(refund_on_failure),
             })
             .add_attribute("action", "exchange"))
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn continue_route(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        dex_router: Addr,
+        operations: Vec<SwapOperation>,
+        prev_balance: Asset,
+        hop_minimum: Option<Uint128>,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        if info.sender != env.contract.address {
+            return Err(ContractError::Unauthorized {});
+        }
+        let amount = asset_balance(deps.as_ref(), &env, &prev_balance.info)?
+            .checked_sub(prev_balance.amount)?;
+        if amount < hop_minimum.unwrap_or_default() {
+            return Err(ContractError::PriceDeviation {});
+        }
+        let Some((operation, operations)) = operations.split_first() else {
+            if amount < minimum_receive.unwrap_or_default() {
+                return Err(ContractError::NotEnoughOutputCoin {});
+            }
+            return Ok(Response::new().add_attribute("action", "continue_route"));
+        };
+        let (target, hop) = match operation {
+            SwapOperation::PairSwap {
+                pair,
+                offer_asset_info,
+                ask_asset_info,
+            } => (
+                pair.to_string(),
+                SwapOperation::AstroSwap {
+                    offer_asset_info: offer_asset_info.clone(),
+                    ask_asset_info: ask_asset_info.clone(),
+                },
+            ),
+            _ => (dex_router.to_string(), operation.clone()),
+        };
+        let swap = to_json_binary(&ExternalExecuteMsg::ExecuteSwapOperations {
+            operations: vec![hop],
+            minimum_receive: None,
+            to: None,
+            max_spread,
+        })?;
+        let swap_msg = match prev_balance.info {
+            AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+                contract_addr: target,
+                msg: swap,
+                funds: vec![Coin::new(amount, denom)],
+            },
+            AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: target,
+                    amount,
+                    msg: swap,
+                })?,
+                funds: vec![],
+            },
+        };
+        let ask_asset_info = operation.ask_asset_info();
+        let hop_minimum = match minimum_receive {
+            None => hop_floor(deps.branch(), operation, amount)?,
+            Some(_) => None,
+        };
+        let next_msg = WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::Callback(CallbackMsg::ContinueRoute {
+                dex_router,
+                operations: operations.to_vec(),
+                prev_balance: Asset {
+                    amount: asset_balance(deps.as_ref(), &env, &ask_asset_info)?,
+                    info: ask_asset_info,
+                },
+                hop_minimum,
+                minimum_receive,
+                max_spread,
+            }))?,
+            funds: vec![],
+        };
+        Ok(Response::new()
+            .add_message(swap_msg)
+            .add_message(next_msg)
+            .add_attribute("action", "continue_route")
+            .add_attribute("offer_amount", amount))
+    }
+
+    /// Pair whose guard floors `operation`: its own pair for a pair hop, the first guarded
+    /// pair of its assets for a router hop.
+    fn guarded_pair(deps: Deps, operation: &SwapOperation) -> StdResult<Option<Addr>> {
+        match operation {
+            SwapOperation::PairSwap { pair, .. } => Ok(Some(pair.clone())),
+            SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+            } => GUARDED_PAIRS
+                .prefix((offer_asset_info.to_string(), ask_asset_info.to_string()))
+                .keys(deps.storage, None, None, Order::Ascending)
+                .next()
+                .transpose(),
+        }
+    }
+
+    /// `price_floor` of one hop, `None` when no guarded pair trades its assets.
+    fn hop_floor(
+        deps: DepsMut,
+        operation: &SwapOperation,
+        amount: Uint128,
+    ) -> Result<Option<Uint128>, ContractError> {
+        let Some(pair) = guarded_pair(deps.as_ref(), operation)? else {
+            return Ok(None);
+        };
+        price_floor(
+            deps,
+            &pair,
+            &operation.offer_asset_info(),
+            &operation.ask_asset_info(),
+            amount,
+        )
+    }
+
+    /// Least `amount` of `offer` may return through `pair` without tripping its price guard.
+    /// The reference price takes precedence over the TWAP; `None` when neither is known.
+    pub(crate) fn price_floor(
+        deps: DepsMut,
+        pair: &Addr,
+        offer: &AssetInfo,
+        ask: &AssetInfo,
+        amount: Uint128,
+    ) -> Result<Option<Uint128>, ContractError> {
+        let Some(guard) = PRICE_GUARDS.may_load(deps.storage, pair.clone())? else {
+            return Ok(None);
+        };
+        let twap = observe_prices(deps, pair)?;
+        let price = price_of(&guard.reference_prices, offer, ask).or(price_of(&twap, offer, ask));
+        Ok(price.map(|price| {
+            amount.mul_floor(price * Decimal::one().saturating_sub(guard.max_deviation))
+        }))
+    }
+
+    fn price_of(
+        prices: &[(AssetInfo, AssetInfo, Decimal)],
+        offer: &AssetInfo,
+        ask: &AssetInfo,
+    ) -> Option<Decimal> {
+        prices
+            .iter()
+            .find(|(o, a, _)| o == offer && a == ask)
+            .map(|(_, _, price)| *price)
+    }
+
+    /// Records the pair's cumulative prices and returns the TWAP since the previous
+    /// observation, or the last TWAP when the pair has not accumulated since.
+    fn observe_prices(
+        deps: DepsMut,
+        pair: &Addr,
+    ) -> StdResult<Vec<(AssetInfo, AssetInfo, Decimal)>> {
+        let prices: CumulativePricesResponse = deps
+            .querier
+            .query_wasm_smart(pair, &ExternalQueryMsg::CumulativePrices {})?;
+        let config: ConfigResponse = deps
+            .querier
+            .query_wasm_smart(pair, &ExternalQueryMsg::Config {})?;
+        let twap = match PRICE_OBSERVATIONS.may_load(deps.storage, pair.clone())? {
+            Some(prev) if config.block_time_last > prev.block_time_last => {
+                let elapsed = (config.block_time_last - prev.block_time_last) as u128;
+                prices
+                    .cumulative_prices
+                    .iter()
+                    .filter_map(|(offer, ask, cumulative)| {
+                        let (_, _, prev_cumulative) = prev
+                            .cumulative_prices
+                            .iter()
+                            .find(|(o, a, _)| o == offer && a == ask)?;
+                        let delta = cumulative.u128().wrapping_sub(prev_cumulative.u128());
+                        let twap = Decimal::from_ratio(delta, elapsed * 10u128.pow(TWAP_PRECISION));
+                        Some((offer.clone(), ask.clone(), twap))
+                    })
+                    .collect()
+            }
+            Some(prev) => return Ok(prev.twap),
+            None => vec![],
+        };
+        PRICE_OBSERVATIONS.save(
+            deps.storage,
+            pair.clone(),
+            &PriceObservation {
+                cumulative_prices: prices.cumulative_prices,
+                block_time_last: config.block_time_last,
+                twap: twap.clone(),
+            },
+        )?;
+        Ok(twap)
+    }
+
+    pub fn set_price_guard(
+        deps: DepsMut,
+        info: MessageInfo,
+        pair: Addr,
+        guard: Option<PriceGuard>,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
+        let pair_info: PairInfo = deps
+            .querier
+            .query_wasm_smart(pair.clone(), &ExternalQueryMsg::Pair {})?;
+        for offer in &pair_info.asset_infos {
+            for ask in pair_info.asset_infos.iter().filter(|ask| *ask != offer) {
+                let key = (offer.to_string(), ask.to_string(), pair.clone());
+                match guard {
+                    Some(_) => GUARDED_PAIRS.save(deps.storage, key, &Empty {})?,
+                    None => GUARDED_PAIRS.remove(deps.storage, key),
+                }
+            }
+        }
+        match guard {
+            Some(guard) => {
+                PRICE_GUARDS.save(deps.storage, pair.clone(), &guard)?;
+                // Start observing now so the TWAP is available from the next swap.
+                observe_prices(deps, &pair)?;
+            }
+            None => {
+                PRICE_GUARDS.remove(deps.storage, pair.clone());
+                PRICE_OBSERVATIONS.remove(deps.storage, pair.clone());
+            }
+        }
+        Ok(Response::new()
+            .add_attribute("action", "set_price_guard")
+            .add_attribute("pair", pair))
+    }
+
+    /// Checks that a route is non-empty and that every hop starts with the previous hop's
+    /// ask asset.
+    pub(crate) fn validate_route(operations: &[SwapOperation]) -> Result<(), ContractError> {
+        if operations.is_empty() {
+            return Err(ContractError::EmptyRoute {});
+        }
+        for (hop, operation) in operations.iter().enumerate() {
+            if operation.offer_asset_info() == operation.ask_asset_info() {
+                return Err(ContractError::InvalidRoute { hop });
+            }
+            if hop > 0 && operations[hop - 1].ask_asset_info() != operation.offer_asset_info() {
+                return Err(ContractError::InvalidRoute { hop });
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn add_liquidity(
         deps: DepsMut,
         env: Env,
@@ -260,12 +757,15 @@ pub mod execute {
         coins: Vec<Coin>,
         slippage_tolerance: Option<Decimal>,
         depositor: String,
+        refund_on_failure: bool,
+        min_lp_out: Option<Uint128>,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::Liquidity])?;
+        // Every coin is paid from the depositor's deposit; refunds and leftovers go back to it.
+        for coin in &coins {
+            debit_balance(deps.storage, &depositor, &coin.denom, coin.amount)?;
+        }
         let pair_info: PairInfo = deps
             .querier
             .query_wasm_smart(pair.clone(), &ExternalQueryMsg::Pair {})?;
@@ -276,11 +776,14 @@ pub mod execute {
             },
         )?;
 
-        if coins.len() == 2 || pair_info.pair_type != (PairType::Xyk {}) {
+        if coins.len() == 2 {
             let payload = to_json_binary(&(
                 depositor,
                 pair_info.liquidity_token.to_string(),
                 init_lp_balance.balance,
+                refund_on_failure.then(|| coins.clone()),
+                min_lp_out,
+                provision_baseline(deps.as_ref(), &env, &coins)?,
             ))?;
             Ok(Response::new()
                 .add_submessage(SubMsg {
@@ -288,15 +791,7 @@ pub mod execute {
                     msg: CosmosMsg::Wasm(WasmMsg::Execute {
                         contract_addr: pair.to_string(),
                         msg: to_json_binary(&ExternalExecuteMsg::ProvideLiquidity {
-                            assets: coins
-                                .iter()
-                                .map(|coin| Asset {
-                                    info: AssetInfo::NativeToken {
-                                        denom: coin.denom.clone(),
-                                    },
-                                    amount: coin.amount,
-                                })
-                                .collect(),
+                            assets: provide_assets(&coins),
                             slippage_tolerance,
                             receiver: None,
                         })?,
@@ -304,63 +799,33 @@ pub mod execute {
                     }),
                     payload,
                     gas_limit: None,
-                    reply_on: ReplyOn::Success,
+                    reply_on: reply_on(refund_on_failure),
                 })
                 .add_attribute("action", "add_liquidity"))
         } else {
-            assert!(coins.len() == 1, "Only 1 or 2 coins are supported");
-            let pool_response: PoolResponse = deps
-                .querier
-                .query_wasm_smart(pair.to_string(), &ExternalQueryMsg::Pool {})?;
+            if coins.len() != 1 {
+                return Err(ContractError::UnsupportedCoinCount {});
+            }
             let input_coin = coins[0].clone();
-            let (reserve_in, reserve_out) = if pool_response.assets[0].info
-                == (AssetInfo::NativeToken {
-                    denom: input_coin.denom.clone(),
-                }) {
-                (
-                    pool_response.assets[0].clone(),
-                    pool_response.assets[1].clone(),
-                )
-            } else {
-                (
-                    pool_response.assets[1].clone(),
-                    pool_response.assets[0].clone(),
-                )
-            };
-            let config_response: ConfigResponse = deps
-                .querier
-                .query_wasm_smart(pair.to_string(), &ExternalQueryMsg::Config {})?;
-            let fee_info_response: FeeInfoResponse = deps.querier.query_wasm_smart(
-                config_response.factory_addr.to_string(),
-                &ExternalQueryMsg::FeeInfo {
-                    pair_type: PairType::Xyk {},
-                },
-            )?;
-            let fee_bps = fee_info_response.total_fee_bps;
-
-            let swap_amount: Uint128 =
-                calculate_swap_amount(input_coin.amount, reserve_in.amount, fee_bps);
+            let (offer_asset_info, ask_denom, swap_amount) =
+                zap_plan(deps.as_ref(), &pair, &pair_info.pair_type, &input_coin)?;
 
-            let coins = vec![
-                Coin {
-                    denom: input_coin.denom.clone(),
-                    amount: input_coin.amount - swap_amount,
-                },
-                deps.querier.query_balance(env.contract.address.clone(), {
-                    if let AssetInfo::NativeToken { denom } = reserve_out.info.clone() {
-                        denom
-                    } else {
-                        return Err(ContractError::UnsupportedCw20 {});
-                    }
-                })?,
-            ];
+            // Only the swap output is provided with the rest of the input, never other
+            // holdings of the ask denom.
+            let ask_balance = deps
+                .querier
+                .query_balance(env.contract.address.clone(), ask_denom.clone())?;
 
             let payload = to_json_binary(&(
                 pair.clone(),
                 depositor,
                 pair_info.liquidity_token.to_string(),
                 init_lp_balance.balance,
-                coins,
+                Coin::new(input_coin.amount - swap_amount, &input_coin.denom),
+                ask_balance,
+                refund_on_failure.then(|| input_coin.clone()),
+                slippage_tolerance,
+                min_lp_out,
             ))?;
             Ok(Response::new()
                 .add_submessage(SubMsg {
@@ -369,12 +834,12 @@ pub mod execute {
                         contract_addr: pair.to_string(),
                         msg: to_json_binary(&ExternalExecuteMsg::ExecuteSwapOperations {
                             operations: vec![SwapOperation::AstroSwap {
-                                offer_asset_info: reserve_in.info,
-                                ask_asset_info: reserve_out.info,
+                                offer_asset_info,
+                                ask_asset_info: AssetInfo::NativeToken { denom: ask_denom },
                             }],
                             minimum_receive: None,
                             to: None,
-                            max_spread: None,
+                            max_spread: slippage_tolerance,
                         })?,
                         funds: vec![Coin {
                             denom: input_coin.denom,
@@ -383,31 +848,279 @@ pub mod execute {
                     }),
                     payload,
                     gas_limit: None,
-                    reply_on: ReplyOn::Success,
+                    reply_on: reply_on(refund_on_failure),
                 })
                 .add_attribute("action", "add_liquidity"))
         }
     }
 
+    fn evm_address(address: &str) -> Result<Address, ContractError> {
+        Address::from_str(address).map_err(|_| ContractError::InvalidEvmAddress {
+            address: address.to_string(),
+        })
+    }
+
+    pub(crate) fn provide_assets(coins: &[Coin]) -> Vec<Asset> {
+        coins
+            .iter()
+            .map(|coin| Asset {
+                info: AssetInfo::NativeToken {
+                    denom: coin.denom.clone(),
+                },
+                amount: coin.amount,
+            })
+            .collect()
+    }
+
+    /// Contract balances expected once `coins` are provided; anything above them after the
+    /// provision was sent back by the pair.
+    pub(crate) fn provision_baseline(
+        deps: Deps,
+        env: &Env,
+        coins: &[Coin],
+    ) -> StdResult<Vec<Coin>> {
+        coins
+            .iter()
+            .map(|coin| {
+                let balance = deps
+                    .querier
+                    .query_balance(&env.contract.address, &coin.denom)?;
+                // Coins the contract does not hold make the provision itself fail.
+                Ok(Coin::new(
+                    balance.amount.saturating_sub(coin.amount),
+                    &coin.denom,
+                ))
+            })
+            .collect()
+    }
+
+    /// Plans the single-sided zap of `input_coin` into `pair`: returns the offer asset, the
+    /// denom received in exchange and how much of the input to swap.
+    pub(crate) fn zap_plan(
+        deps: Deps,
+        pair: &Addr,
+        pair_type: &PairType,
+        input_coin: &Coin,
+    ) -> Result<(AssetInfo, String, Uint128), ContractError> {
+        let pool_response: PoolResponse = deps
+            .querier
+            .query_wasm_smart(pair.to_string(), &ExternalQueryMsg::Pool {})?;
+        let (reserve_in, reserve_out) = if pool_response.assets[0].info
+            == (AssetInfo::NativeToken {
+                denom: input_coin.denom.clone(),
+            }) {
+            (
+                pool_response.assets[0].clone(),
+                pool_response.assets[1].clone(),
+            )
+        } else {
+            (
+                pool_response.assets[1].clone(),
+                pool_response.assets[0].clone(),
+            )
+        };
+        let AssetInfo::NativeToken { denom: ask_denom } = reserve_out.info.clone() else {
+            return Err(ContractError::UnsupportedCw20 {});
+        };
+        if let PairType::Custom(_) = pair_type {
+            let swap_amount =
+                calculate_simulated_swap_amount(deps, pair, &reserve_in, &reserve_out, input_coin)?;
+            return Ok((reserve_in.info, ask_denom, swap_amount));
+        }
+        let config_response: ConfigResponse = deps
+            .querier
+            .query_wasm_smart(pair.to_string(), &ExternalQueryMsg::Config {})?;
+        let fee_info_response: FeeInfoResponse = deps.querier.query_wasm_smart(
+            config_response.factory_addr.to_string(),
+            &ExternalQueryMsg::FeeInfo {
+                pair_type: pair_type.clone(),
+            },
+        )?;
+        let fee_bps = fee_info_response.total_fee_bps;
+        let swap_amount = match pair_type {
+            PairType::Stable {} => {
+                let params = config_response
+                    .params
+                    .ok_or_else(|| StdError::generic_err("Stable pair without params"))?;
+                let config: StablePoolConfig = from_json(params)?;
+                calculate_stable_swap_amount(
+                    input_coin.amount,
+                    reserve_in.amount,
+                    reserve_out.amount,
+                    (
+                        native_precision(deps, &input_coin.denom),
+                        native_precision(deps, &ask_denom),
+                    ),
+                    config.amp,
+                    fee_bps,
+                )?
+            }
+            _ => calculate_swap_amount(input_coin.amount, reserve_in.amount, fee_bps),
+        };
+        Ok((reserve_in.info, ask_denom, swap_amount))
+    }
+
+    /// Decimals of a native denom: the largest exponent of its bank metadata, or
+    /// `DEFAULT_PRECISION` when the chain has none registered.
+    fn native_precision(deps: Deps, denom: &str) -> u32 {
+        deps.querier
+            .query_denom_metadata(denom)
+            .ok()
+            .and_then(|metadata| metadata.denom_units.iter().map(|unit| unit.exponent).max())
+            .unwrap_or(DEFAULT_PRECISION)
+    }
+
     fn calculate_swap_amount(input_amount: Uint128, reserve_in: Uint128, fee_bps: u16) -> Uint128 {
+        if reserve_in.is_zero() {
+            return Uint128::zero();
+        }
+        // Closed-form XYK zap: swap s of input a into reserve R so that the
+        // remainder matches the pool ratio, i.e.
+        // s = R * (sqrt((1 + r)^2 + 4 * r * a / R) - (1 + r)) / (2 * r).
         let receive_rate = Decimal256::from_ratio(10000 - fee_bps, 10000u16);
-        (((Decimal256::one() + receive_rate).pow(2)
-            + Decimal256::raw(4)
+        let one_plus_rate = Decimal256::one() + receive_rate;
+        let fraction = ((one_plus_rate.pow(2)
+            + Decimal256::from_ratio(4u8, 1u8)
                 * receive_rate
-                * Decimal256::new(input_amount.into())
-                * Decimal256::new(reserve_in.into()))
+                * Decimal256::from_ratio(input_amount, reserve_in))
         .sqrt()
-            - Decimal256::one()
-            - receive_rate)
-            .checked_div(Decimal256::raw(2))
-            .unwrap()
-            .checked_mul(Decimal256::new(reserve_in.into()))
-            .unwrap()
-            .to_uint_floor()
+            - one_plus_rate)
+            / (receive_rate + receive_rate);
+        Uint256::from(reserve_in)
+            .mul_floor(fraction)
+            .min(input_amount.into())
             .try_into()
             .unwrap()
     }
 
+    /// Swap amount for a single-sided stableswap deposit, found by bisection so that the
+    /// remaining input and the swap output match the ratio of the reserves after the swap.
+    /// Reserves are compared at the larger of the two assets' precisions.
+    fn calculate_stable_swap_amount(
+        input_amount: Uint128,
+        reserve_in: Uint128,
+        reserve_out: Uint128,
+        (precision_in, precision_out): (u32, u32),
+        amp: Decimal,
+        fee_bps: u16,
+    ) -> StdResult<Uint128> {
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            return Ok(Uint128::zero());
+        }
+        // Two-asset stableswap with leverage A * n, as the Palomadex stable pair uses. Like
+        // the pair, it compares both assets scaled to the larger of their precisions.
+        let leverage = Decimal256::from(amp) * Decimal256::from_ratio(2u8, 1u8);
+        let precision = precision_in.max(precision_out);
+        let scale_in = Uint256::from(10u8).checked_pow(precision - precision_in)?;
+        let scale_out = Uint256::from(10u8).checked_pow(precision - precision_out)?;
+        let (x, y) = (
+            Uint256::from(reserve_in) * scale_in,
+            Uint256::from(reserve_out) * scale_out,
+        );
+        let d = stable_d(leverage, x, y)?;
+        let swap_output = |s: Uint256| -> StdResult<Uint256> {
+            let new_y = stable_y(leverage, d, x + s)?;
+            let out = y.saturating_sub(new_y).saturating_sub(Uint256::one());
+            Ok(out - out.multiply_ratio(fee_bps, 10000u16))
+        };
+        let a = Uint256::from(input_amount) * scale_in;
+        let (mut low, mut high) = (Uint256::zero(), a);
+        while high - low > Uint256::one() {
+            let mid = (low + high) / Uint256::from(2u8);
+            let out = swap_output(mid)?;
+            // Remaining input per reserve in against output per reserve out.
+            if (a - mid) * (y - out) > out * (x + mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Ok((low / scale_in).try_into()?)
+    }
+
+    /// Swap amount for a single-sided deposit into a pair of unknown invariant, bisected like
+    /// `calculate_stable_swap_amount` but pricing each candidate with the pair's own
+    /// `Simulation` query. Stops within a basis point of the input to bound the queries.
+    fn calculate_simulated_swap_amount(
+        deps: Deps,
+        pair: &Addr,
+        reserve_in: &Asset,
+        reserve_out: &Asset,
+        input_coin: &Coin,
+    ) -> StdResult<Uint128> {
+        if reserve_in.amount.is_zero() || reserve_out.amount.is_zero() {
+            return Ok(Uint128::zero());
+        }
+        let swap_output = |s: Uint128| -> StdResult<Uint256> {
+            let response: SimulationResponse = deps.querier.query_wasm_smart(
+                pair,
+                &ExternalQueryMsg::Simulation {
+                    offer_asset: Asset {
+                        info: reserve_in.info.clone(),
+                        amount: s,
+                    },
+                    ask_asset_info: Some(reserve_out.info.clone()),
+                },
+            )?;
+            Ok(response.return_amount.into())
+        };
+        let (x, y) = (
+            Uint256::from(reserve_in.amount),
+            Uint256::from(reserve_out.amount),
+        );
+        let a = input_coin.amount;
+        let tolerance = (a / Uint128::new(10_000)).max(Uint128::one());
+        let (mut low, mut high) = (Uint128::zero(), a);
+        while high - low > tolerance {
+            let mid = (low + high) / Uint128::new(2);
+            let out = swap_output(mid)?;
+            // Remaining input per reserve in against output per reserve out.
+            if Uint256::from(a - mid) * y.saturating_sub(out) > out * (x + Uint256::from(mid)) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Stableswap invariant D of reserves `x` and `y`.
+    fn stable_d(leverage: Decimal256, x: Uint256, y: Uint256) -> StdResult<Uint256> {
+        let two = Uint256::from(2u8);
+        let sum = x + y;
+        let mut d = sum;
+        for _ in 0..64 {
+            let d_p = d.multiply_ratio(d, x * two) * d / (y * two);
+            let prev = d;
+            d = (sum.mul_floor(leverage) + d_p * two) * d
+                / (d.mul_floor(leverage - Decimal256::one()) + d_p * Uint256::from(3u8));
+            if d.abs_diff(prev) <= Uint256::one() {
+                return Ok(d);
+            }
+        }
+        Err(StdError::generic_err(
+            "Stableswap invariant did not converge",
+        ))
+    }
+
+    /// Reserve of the other asset that keeps invariant `d` when one reserve is `x`.
+    fn stable_y(leverage: Decimal256, d: Uint256, x: Uint256) -> StdResult<Uint256> {
+        let two = Uint256::from(2u8);
+        let precision = Uint256::from(10u128.pow(Decimal256::DECIMAL_PLACES));
+        let c = d.multiply_ratio(d, x * two) * d / two * precision / leverage.atomics();
+        let b = x + d * precision / leverage.atomics();
+        let mut y = d;
+        for _ in 0..64 {
+            let prev = y;
+            y = (y * y + c) / (y * two + b - d);
+            if y.abs_diff(prev) <= Uint256::one() {
+                return Ok(y);
+            }
+        }
+        Err(StdError::generic_err("Stableswap reserve did not converge"))
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn remove_liquidity(
         deps: DepsMut,
         env: Env,
@@ -416,39 +1129,72 @@ pub mod execute {
         pair: Addr,
         amount: Uint128,
         receiver: String,
+        min_assets_out: Vec<Coin>,
+        receive_asset: Option<String>,
+        delivery: Delivery,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::Liquidity])?;
+        check_delivery(deps.as_ref(), &delivery)?;
+        if receive_asset.is_some() {
+            assert_not_paused(deps.storage, &[PauseCategory::Swaps])?;
+        }
 
         let pair_info: PairInfo = deps
             .querier
             .query_wasm_smart(pair.clone(), &ExternalQueryMsg::Pair {})?;
         let lp_token = pair_info.liquidity_token;
 
-        let mut coins: Vec<Coin> = vec![];
-        pair_info.asset_infos.iter().for_each(|asset| {
-            if let AssetInfo::NativeToken { denom } = asset {
-                coins.push(
-                    deps.querier
-                        .query_balance(env.contract.address.clone(), denom)
-                        .unwrap(),
-                );
+        let balances = pair_info
+            .asset_infos
+            .iter()
+            .map(|info| {
+                Ok(Asset {
+                    info: info.clone(),
+                    amount: asset_balance(deps.as_ref(), &env, info)?,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        if let Some(denom) = &receive_asset {
+            let receive_asset_info = AssetInfo::NativeToken {
+                denom: denom.clone(),
+            };
+            if !pair_info.asset_infos.contains(&receive_asset_info) {
+                return Err(ContractError::InvalidReceiveAsset {
+                    denom: denom.clone(),
+                });
             }
-        });
+            // The other asset is swapped through the pair in one native hop.
+            let natives = pair_info
+                .asset_infos
+                .iter()
+                .filter(|info| matches!(info, AssetInfo::NativeToken { .. }))
+                .count();
+            if pair_info.asset_infos.len() != 2 || natives != 2 {
+                return Err(ContractError::UnsupportedZapOut {});
+            }
+        }
         let lp_balance =
             LP_BALANCES.load(deps.storage, (receiver.clone(), lp_token.to_string()))?;
         if lp_balance < amount {
             return Err(ContractError::InsufficientLiquidity {});
         }
-        LP_BALANCES.update(
+        update_lp_balance(
             deps.storage,
             (receiver.clone(), lp_token.to_string()),
             |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() - amount) },
         )?;
-        let payload = to_json_binary(&(coins, receiver, chain_id, lp_token.to_string()))?;
+        assert_solvent(deps.as_ref(), &env, lp_token.as_str(), amount)?;
+        let payload = to_json_binary(&(
+            balances,
+            receiver,
+            chain_id,
+            lp_token.to_string(),
+            min_assets_out,
+            pair.to_string(),
+            receive_asset,
+            delivery,
+        ))?;
 
         Ok(Response::new()
             .add_submessage(SubMsg {
@@ -471,49 +1217,46 @@ pub mod execute {
             .add_attribute("action", "remove_liquidity"))
     }
 
+    /// Bridging is subject to the `Bridging` pause and `Send` needs a valid Paloma address.
+    fn check_delivery(deps: Deps, delivery: &Delivery) -> Result<(), ContractError> {
+        match delivery {
+            Delivery::Bridge => assert_not_paused(deps.storage, &[PauseCategory::Bridging]),
+            Delivery::Send { address } => {
+                deps.api.addr_validate(address)?;
+                Ok(())
+            }
+            Delivery::Credit => Ok(()),
+        }
+    }
+
     pub fn send_to_evm(
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         chain_id: String,
         amounts: Vec<String>,
         recipient: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
-        let incentives_setting = INCENTIVES_SETTING.load(deps.storage)?;
-        let messages = amounts
-            .iter()
-            .map(|amount| {
-                let coin = Coin::from_str(amount).unwrap();
-                if coin.denom == incentives_setting.padex {
-                    LP_BALANCES
-                        .update(
-                            deps.storage,
-                            (recipient.clone(), incentives_setting.padex.clone()),
-                            |lp_balance: Option<Uint128>| -> StdResult<_> {
-                                let mut balance = lp_balance.unwrap_or_default();
-                                if balance > coin.amount {
-                                    balance -= coin.amount;
-                                }
-                                Ok(balance)
-                            },
-                        )
-                        .unwrap();
-                }
-                CosmosMsg::Custom(PalomaMsg::SkywayMsg {
-                    send_tx: Some(SendTx {
-                        remote_chain_destination_address: recipient.clone(),
-                        amount: amount.clone(),
-                        chain_reference_id: chain_id.clone(),
-                    }),
-                    cancel_tx: None,
-                })
-            })
-            .collect::<Vec<_>>();
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::Bridging])?;
+        let mut messages: Vec<CosmosMsg<PalomaMsg>> = vec![];
+        let mut outflows: BTreeMap<String, Uint128> = BTreeMap::new();
+        for amount in amounts.iter() {
+            let coin = Coin::from_str(amount).map_err(StdError::from)?;
+            *outflows.entry(coin.denom.clone()).or_default() += coin.amount;
+            debit_balance(deps.storage, &recipient, &coin.denom, coin.amount)?;
+            messages.push(CosmosMsg::Custom(PalomaMsg::SkywayMsg {
+                send_tx: Some(SendTx {
+                    remote_chain_destination_address: recipient.clone(),
+                    amount: amount.clone(),
+                    chain_reference_id: chain_id.clone(),
+                }),
+                cancel_tx: None,
+            }));
+        }
+        for (denom, outflow) in outflows {
+            assert_solvent(deps.as_ref(), &env, &denom, outflow)?;
+        }
         Ok(Response::new()
             .add_messages(messages)
             .add_attribute("action", "send_to_evm"))
@@ -528,34 +1271,18 @@ pub mod execute {
         amount: Uint128,
         depositor: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
-        LP_BALANCES.update(
-            deps.storage,
-            (depositor.clone(), token.clone()),
-            |lp_balance: Option<Uint128>| -> StdResult<_> {
-                let balance = lp_balance.unwrap_or_default();
-                assert!(balance >= amount, "Insufficient balance");
-                Ok(balance - amount)
-            },
-        )?;
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::Incentives])?;
+        debit_balance(deps.storage, &depositor, &token, amount)?;
         let incentives_setting = INCENTIVES_SETTING.load(deps.storage)?;
         let padex_balance = deps
             .querier
-            .query_balance(env.contract.address.clone(), incentives_setting.padex)
-            .unwrap();
+            .query_balance(env.contract.address.clone(), incentives_setting.padex)?;
         let payload = to_json_binary(&(depositor.clone(), padex_balance))?;
         let msg = WasmMsg::Execute {
             contract_addr: token.clone(),
             msg: to_json_binary(&Cw20ExecuteMsg::Send {
-                contract: deps
-                    .api
-                    .addr_validate(incentivizer.as_str())
-                    .unwrap()
-                    .to_string(),
+                contract: deps.api.addr_validate(incentivizer.as_str())?.to_string(),
                 amount,
                 msg: to_json_binary(&Cw20Msg::Deposit {
                     recipient: Some(depositor),
@@ -585,24 +1312,13 @@ pub mod execute {
         amount: Uint128,
         recipient: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
-        LP_BALANCES.update(
-            deps.storage,
-            (recipient.clone(), token.clone()),
-            |lp_balance: Option<Uint128>| -> StdResult<_> {
-                let balance = lp_balance.unwrap_or_default();
-                Ok(balance + amount)
-            },
-        )?;
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::Incentives])?;
+        credit_balance(deps.storage, &recipient, &token, amount)?;
         let incentives_setting = INCENTIVES_SETTING.load(deps.storage)?;
         let padex_balance = deps
             .querier
-            .query_balance(env.contract.address.clone(), incentives_setting.padex)
-            .unwrap();
+            .query_balance(env.contract.address.clone(), incentives_setting.padex)?;
         let payload = to_json_binary(&(recipient.clone(), padex_balance))?;
         let msg = WasmMsg::Execute {
             contract_addr: deps.api.addr_validate(incentivizer.as_str())?.to_string(),
@@ -634,11 +1350,8 @@ pub mod execute {
         lp_tokens: Vec<String>,
         user: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::Incentives])?;
         let msg = WasmMsg::Execute {
             contract_addr: deps.api.addr_validate(incentivizer.as_str())?.to_string(),
             msg: to_json_binary(&IncentivizerExecuteMsg::ClaimRewards {
@@ -650,8 +1363,7 @@ pub mod execute {
         let incentives_setting = INCENTIVES_SETTING.load(deps.storage)?;
         let padex_balance = deps
             .querier
-            .query_balance(env.contract.address.clone(), incentives_setting.padex)
-            .unwrap();
+            .query_balance(env.contract.address.clone(), incentives_setting.padex)?;
         let payload = to_json_binary(&(user, padex_balance))?;
         Ok(Response::new()
             .add_submessage(SubMsg {
@@ -673,16 +1385,12 @@ pub mod execute {
         end_lock_time: u64,
         user: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::VePadex])?;
         let incentives_setting = INCENTIVES_SETTING.load(deps.storage)?;
         let padex_balance = deps
             .querier
-            .query_balance(env.contract.address.clone(), incentives_setting.padex)
-            .unwrap();
+            .query_balance(env.contract.address.clone(), incentives_setting.padex)?;
         let payload = to_json_binary(&(user.clone(), padex_balance))?;
         let msg = WasmMsg::Execute {
             contract_addr: vepadex.to_string(),
@@ -711,16 +1419,12 @@ pub mod execute {
         user: String,
         coin: Coin,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::VePadex])?;
         let incentives_setting = INCENTIVES_SETTING.load(deps.storage)?;
         let padex_balance = deps
             .querier
-            .query_balance(env.contract.address.clone(), incentives_setting.padex)
-            .unwrap();
+            .query_balance(env.contract.address.clone(), incentives_setting.padex)?;
         let payload = to_json_binary(&(user.clone(), padex_balance))?;
         let msg = WasmMsg::Execute {
             contract_addr: vepadex.to_string(),
@@ -745,16 +1449,12 @@ pub mod execute {
         vepadex: Addr,
         user: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::VePadex])?;
         let incentives_setting = INCENTIVES_SETTING.load(deps.storage)?;
         let padex_balance = deps
             .querier
-            .query_balance(env.contract.address.clone(), incentives_setting.padex)
-            .unwrap();
+            .query_balance(env.contract.address.clone(), incentives_setting.padex)?;
         let payload = to_json_binary(&(user.clone(), padex_balance))?;
         let msg = WasmMsg::Execute {
             contract_addr: vepadex.to_string(),
@@ -779,11 +1479,8 @@ pub mod execute {
         end_lock_time: u64,
         user: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
+        assert_not_paused(deps.storage, &[PauseCategory::VePadex])?;
         let msg = WasmMsg::Execute {
             contract_addr: vepadex.to_string(),
             msg: to_json_binary(&VePadexExecuteMsg::IncreaseEndLockTime {
@@ -799,23 +1496,16 @@ pub mod execute {
 
     pub fn add_lp_token(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         lp_token: String,
         user: String,
         amount: Uint128,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
-        LP_BALANCES.update(
-            deps.storage,
-            (user.clone(), lp_token.clone()),
-            |lp_balance: Option<Uint128>| -> StdResult<_> {
-                Ok(lp_balance.unwrap_or_default() + amount)
-            },
-        )?;
+        assert_owner(deps.storage, &info.sender)?;
+        credit_balance(deps.storage, &user, &lp_token, amount)?;
+        // A credit must be backed by tokens the contract already holds.
+        assert_solvent(deps.as_ref(), &env, &lp_token, Uint128::zero())?;
         Ok(Response::new()
             .add_attribute("action", "add_lp_token")
             .add_attribute("lp_token", lp_token)
@@ -830,11 +1520,7 @@ pub mod execute {
         compass_job_id: String,
         main_job_id: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_owner(deps.storage, &info.sender)?;
         CHAIN_SETTINGS.save(
             deps.storage,
             chain_id.clone(),
@@ -847,18 +1533,170 @@ pub mod execute {
         Ok(Response::new().add_attribute("action", "set_chain_setting"))
     }
 
-    pub fn set_paloma(
+    pub fn propose_admin_action(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        chain_id: String,
+        action: AdminAction,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        // ACTION: Implement SetPaloma
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        let state = match action {
+            AdminAction::UpdateGasFee { .. }
+            | AdminAction::UpdateServiceFeeCollector { .. }
+            | AdminAction::UpdateServiceFee { .. } => {
+                assert_role(deps.storage, &info.sender, Role::FeeManager)?
+            }
+            AdminAction::SetPaloma { .. }
+            | AdminAction::UpdateRefundWallet { .. }
+            | AdminAction::UpdateApprovalThreshold { .. }
+            | AdminAction::ProposeOwner { .. }
+            | AdminAction::RemoveOwner { .. } => assert_owner(deps.storage, &info.sender)?,
+        };
+        let multisig_setting = MULTISIG_SETTING.load(deps.storage)?;
+        // Validate up front so unknown chains, bad addresses and out of range thresholds fail
+        // at proposal time.
+        match &action {
+            AdminAction::UpdateApprovalThreshold { approval_threshold } => assert_multisig_setting(
+                &state,
+                &MultisigSetting {
+                    approval_threshold: *approval_threshold,
+                    ..multisig_setting.clone()
+                },
+            )?,
+            AdminAction::ProposeOwner { owners } => {
+                for owner in owners.iter() {
+                    deps.api.addr_validate(owner)?;
+                }
+            }
+            AdminAction::RemoveOwner { owner } => {
+                remove_owner(deps.as_ref(), owner)?;
+            }
+            _ => {
+                admin_action_msg(deps.as_ref(), &action)?;
+            }
+        }
+        let proposal_id = PROPOSAL_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+        PROPOSAL_COUNT.save(deps.storage, &proposal_id)?;
+        let mut approvals = vec![];
+        if state.owners.contains(&info.sender) {
+            approvals.push(info.sender.clone());
+        }
+        let proposal = Proposal {
+            action,
+            proposer: info.sender,
+            approvals,
+            expires_at: env.block.time.plus_seconds(multisig_setting.proposal_ttl),
+        };
+        let response = Response::new()
+            .add_attribute("action", "propose_admin_action")
+            .add_attribute("proposal_id", proposal_id.to_string());
+        execute_if_approved(deps, proposal_id, proposal, response)
+    }
+
+    pub fn approve_proposal(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        proposal_id: u64,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
+        let mut proposal = PROPOSALS
+            .may_load(deps.storage, proposal_id)?
+            .ok_or(ContractError::ProposalNotFound {})?;
+        if proposal.expires_at <= env.block.time {
+            return Err(ContractError::ProposalExpired {});
+        }
+        if proposal.approvals.contains(&info.sender) {
+            return Err(ContractError::AlreadyApproved {});
+        }
+        proposal.approvals.push(info.sender);
+        let response = Response::new()
+            .add_attribute("action", "approve_proposal")
+            .add_attribute("proposal_id", proposal_id.to_string());
+        execute_if_approved(deps, proposal_id, proposal, response)
+    }
+
+    /// Dispatches the proposal once enough current owners approved it, otherwise stores it.
+    fn execute_if_approved(
+        deps: DepsMut,
+        proposal_id: u64,
+        proposal: Proposal,
+        response: Response<PalomaMsg>,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        let owners = STATE.load(deps.storage)?.owners;
+        let threshold = MULTISIG_SETTING.load(deps.storage)?.approval_threshold;
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|x| owners.contains(x))
+            .count() as u64;
+        if approvals < threshold {
+            PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+            return Ok(response.add_attribute("approvals", approvals.to_string()));
+        }
+        PROPOSALS.remove(deps.storage, proposal_id);
+        let response = response.add_attribute("executed", proposal.action.name());
+        match proposal.action {
+            AdminAction::UpdateApprovalThreshold { approval_threshold } => {
+                let state = STATE.load(deps.storage)?;
+                let mut multisig_setting = MULTISIG_SETTING.load(deps.storage)?;
+                multisig_setting.approval_threshold = approval_threshold;
+                // Owners may have been removed since the proposal was opened.
+                assert_multisig_setting(&state, &multisig_setting)?;
+                MULTISIG_SETTING.save(deps.storage, &multisig_setting)?;
+                Ok(response)
+            }
+            AdminAction::ProposeOwner { owners } => {
+                let state = STATE.load(deps.storage)?;
+                for owner in owners.iter() {
+                    let owner = deps.api.addr_validate(owner)?;
+                    if !state.owners.contains(&owner) {
+                        PENDING_OWNERS.save(deps.storage, owner, &proposal.proposer)?;
+                    }
+                }
+                Ok(response)
+            }
+            AdminAction::RemoveOwner { owner } => {
+                // Owners and the threshold may have changed since the proposal was opened.
+                let state = remove_owner(deps.as_ref(), &owner)?;
+                STATE.save(deps.storage, &state)?;
+                Ok(response.add_attribute("owner", owner))
+            }
+            action => Ok(response.add_message(admin_action_msg(deps.as_ref(), &action)?)),
+        }
+    }
+
+    /// Encodes the Compass job of a cross-chain admin action.
+    fn admin_action_msg(
+        deps: Deps,
+        action: &AdminAction,
+    ) -> Result<CosmosMsg<PalomaMsg>, ContractError> {
+        match action.clone() {
+            AdminAction::SetPaloma { chain_id } => set_paloma_msg(deps, chain_id),
+            AdminAction::UpdateRefundWallet {
+                chain_id,
+                new_refund_wallet,
+            } => update_refund_wallet_msg(deps, chain_id, new_refund_wallet),
+            AdminAction::UpdateGasFee {
+                chain_id,
+                new_gas_fee,
+            } => update_gas_fee_msg(deps, chain_id, new_gas_fee),
+            AdminAction::UpdateServiceFeeCollector {
+                chain_id,
+                new_service_fee_collector,
+            } => update_service_fee_collector_msg(deps, chain_id, new_service_fee_collector),
+            AdminAction::UpdateServiceFee {
+                chain_id,
+                new_service_fee,
+            } => update_service_fee_msg(deps, chain_id, new_service_fee),
+            AdminAction::UpdateApprovalThreshold { .. }
+            | AdminAction::ProposeOwner { .. }
+            | AdminAction::RemoveOwner { .. } => Err(ContractError::Std(StdError::generic_err(
+                "admin action has no Compass job",
+            ))),
+        }
+    }
 
+    fn set_paloma_msg(deps: Deps, chain_id: String) -> Result<CosmosMsg<PalomaMsg>, ContractError> {
         #[allow(deprecated)]
         let contract: Contract = Contract {
             constructor: None,
@@ -877,36 +1715,27 @@ pub mod execute {
             receive: false,
             fallback: false,
         };
-        Ok(Response::new()
-            .add_message(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
-                execute_job: ExecuteJob {
-                    job_id: CHAIN_SETTINGS
-                        .load(deps.storage, chain_id.clone())?
-                        .main_job_id,
-                    payload: Binary::new(
-                        contract
-                            .function("set_paloma")
-                            .unwrap()
-                            .encode_input(&[])
-                            .unwrap(),
-                    ),
-                },
-            }))
-            .add_attribute("action", "set_paloma"))
+        Ok(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
+            execute_job: ExecuteJob {
+                job_id: CHAIN_SETTINGS
+                    .load(deps.storage, chain_id.clone())?
+                    .main_job_id,
+                payload: Binary::new(
+                    contract
+                        .function("set_paloma")
+                        .unwrap()
+                        .encode_input(&[])
+                        .unwrap(),
+                ),
+            },
+        }))
     }
-    pub fn update_refund_wallet(
-        deps: DepsMut,
-        info: MessageInfo,
+    fn update_refund_wallet_msg(
+        deps: Deps,
         chain_id: String,
         new_refund_wallet: String,
-    ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
-        let update_refund_wallet_address: Address =
-            Address::from_str(new_refund_wallet.as_str()).unwrap();
+    ) -> Result<CosmosMsg<PalomaMsg>, ContractError> {
+        let update_refund_wallet_address: Address = evm_address(&new_refund_wallet)?;
         #[allow(deprecated)]
         let contract: Contract = Contract {
             constructor: None,
@@ -929,35 +1758,27 @@ pub mod execute {
             receive: false,
             fallback: false,
         };
-        Ok(Response::new()
-            .add_message(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
-                execute_job: ExecuteJob {
-                    job_id: CHAIN_SETTINGS
-                        .load(deps.storage, chain_id.clone())?
-                        .main_job_id,
-                    payload: Binary::new(
-                        contract
-                            .function("update_refund_wallet")
-                            .unwrap()
-                            .encode_input(&[Token::Address(update_refund_wallet_address)])
-                            .unwrap(),
-                    ),
-                },
-            }))
-            .add_attribute("action", "update_refund_wallet"))
+        Ok(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
+            execute_job: ExecuteJob {
+                job_id: CHAIN_SETTINGS
+                    .load(deps.storage, chain_id.clone())?
+                    .main_job_id,
+                payload: Binary::new(
+                    contract
+                        .function("update_refund_wallet")
+                        .unwrap()
+                        .encode_input(&[Token::Address(update_refund_wallet_address)])
+                        .unwrap(),
+                ),
+            },
+        }))
     }
 
-    pub fn update_gas_fee(
-        deps: DepsMut,
-        info: MessageInfo,
+    fn update_gas_fee_msg(
+        deps: Deps,
         chain_id: String,
         new_gas_fee: Uint256,
-    ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+    ) -> Result<CosmosMsg<PalomaMsg>, ContractError> {
         #[allow(deprecated)]
         let contract: Contract = Contract {
             constructor: None,
@@ -980,39 +1801,31 @@ pub mod execute {
             receive: false,
             fallback: false,
         };
-        Ok(Response::new()
-            .add_message(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
-                execute_job: ExecuteJob {
-                    job_id: CHAIN_SETTINGS
-                        .load(deps.storage, chain_id.clone())?
-                        .main_job_id,
-                    payload: Binary::new(
-                        contract
-                            .function("update_gas_fee")
-                            .unwrap()
-                            .encode_input(&[Token::Uint(Uint::from_big_endian(
-                                &new_gas_fee.to_be_bytes(),
-                            ))])
-                            .unwrap(),
-                    ),
-                },
-            }))
-            .add_attribute("action", "update_gas_fee"))
+        Ok(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
+            execute_job: ExecuteJob {
+                job_id: CHAIN_SETTINGS
+                    .load(deps.storage, chain_id.clone())?
+                    .main_job_id,
+                payload: Binary::new(
+                    contract
+                        .function("update_gas_fee")
+                        .unwrap()
+                        .encode_input(&[Token::Uint(Uint::from_big_endian(
+                            &new_gas_fee.to_be_bytes(),
+                        ))])
+                        .unwrap(),
+                ),
+            },
+        }))
     }
 
-    pub fn update_service_fee_collector(
-        deps: DepsMut,
-        info: MessageInfo,
+    fn update_service_fee_collector_msg(
+        deps: Deps,
         chain_id: String,
         new_service_fee_collector: String,
-    ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+    ) -> Result<CosmosMsg<PalomaMsg>, ContractError> {
         let update_service_fee_collector_address: Address =
-            Address::from_str(new_service_fee_collector.as_str()).unwrap();
+            evm_address(&new_service_fee_collector)?;
         #[allow(deprecated)]
         let contract: Contract = Contract {
             constructor: None,
@@ -1035,35 +1848,27 @@ pub mod execute {
             receive: false,
             fallback: false,
         };
-        Ok(Response::new()
-            .add_message(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
-                execute_job: ExecuteJob {
-                    job_id: CHAIN_SETTINGS
-                        .load(deps.storage, chain_id.clone())?
-                        .main_job_id,
-                    payload: Binary::new(
-                        contract
-                            .function("update_service_fee_collector")
-                            .unwrap()
-                            .encode_input(&[Token::Address(update_service_fee_collector_address)])
-                            .unwrap(),
-                    ),
-                },
-            }))
-            .add_attribute("action", "update_service_fee_collector"))
+        Ok(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
+            execute_job: ExecuteJob {
+                job_id: CHAIN_SETTINGS
+                    .load(deps.storage, chain_id.clone())?
+                    .main_job_id,
+                payload: Binary::new(
+                    contract
+                        .function("update_service_fee_collector")
+                        .unwrap()
+                        .encode_input(&[Token::Address(update_service_fee_collector_address)])
+                        .unwrap(),
+                ),
+            },
+        }))
     }
 
-    pub fn update_service_fee(
-        deps: DepsMut,
-        info: MessageInfo,
+    fn update_service_fee_msg(
+        deps: Deps,
         chain_id: String,
         new_service_fee: Uint256,
-    ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+    ) -> Result<CosmosMsg<PalomaMsg>, ContractError> {
         #[allow(deprecated)]
         let contract: Contract = Contract {
             constructor: None,
@@ -1086,81 +1891,149 @@ pub mod execute {
             receive: false,
             fallback: false,
         };
-        Ok(Response::new()
-            .add_message(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
-                execute_job: ExecuteJob {
-                    job_id: CHAIN_SETTINGS
-                        .load(deps.storage, chain_id.clone())?
-                        .main_job_id,
-                    payload: Binary::new(
-                        contract
-                            .function("update_service_fee")
-                            .unwrap()
-                            .encode_input(&[Token::Uint(Uint::from_big_endian(
-                                &new_service_fee.to_be_bytes(),
-                            ))])
-                            .unwrap(),
-                    ),
-                },
-            }))
-            .add_attribute("action", "update_service_fee"))
+        Ok(CosmosMsg::Custom(PalomaMsg::SchedulerMsg {
+            execute_job: ExecuteJob {
+                job_id: CHAIN_SETTINGS
+                    .load(deps.storage, chain_id.clone())?
+                    .main_job_id,
+                payload: Binary::new(
+                    contract
+                        .function("update_service_fee")
+                        .unwrap()
+                        .encode_input(&[Token::Uint(Uint::from_big_endian(
+                            &new_service_fee.to_be_bytes(),
+                        ))])
+                        .unwrap(),
+                ),
+            },
+        }))
     }
 
     pub fn update_config(
         deps: DepsMut,
         info: MessageInfo,
         retry_delay: Option<u64>,
+        proposal_ttl: Option<u64>,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let mut state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        let mut state = assert_owner(deps.storage, &info.sender)?;
         if let Some(retry_delay) = retry_delay {
             state.retry_delay = retry_delay;
         }
+        let mut multisig_setting = MULTISIG_SETTING.load(deps.storage)?;
+        if let Some(proposal_ttl) = proposal_ttl {
+            multisig_setting.proposal_ttl = proposal_ttl;
+        }
+        assert_multisig_setting(&state, &multisig_setting)?;
         STATE.save(deps.storage, &state)?;
+        MULTISIG_SETTING.save(deps.storage, &multisig_setting)?;
         Ok(Response::new().add_attribute("action", "update_config"))
     }
 
-    pub fn add_owner(
+    pub fn accept_ownership(
         deps: DepsMut,
         info: MessageInfo,
-        owners: Vec<String>,
     ) -> Result<Response<PalomaMsg>, ContractError> {
+        if !PENDING_OWNERS.has(deps.storage, info.sender.clone()) {
+            return Err(ContractError::Unauthorized {});
+        }
+        PENDING_OWNERS.remove(deps.storage, info.sender.clone());
         let mut state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
-        for owner in owners.iter() {
-            let owner = deps.api.addr_validate(owner)?;
-            if !state.owners.iter().any(|x| x == owner) {
-                state.owners.push(owner);
-            }
+        if !state.owners.contains(&info.sender) {
+            state.owners.push(info.sender.clone());
         }
         STATE.save(deps.storage, &state)?;
-        Ok(Response::new().add_attribute("action", "update_config"))
+        Ok(Response::new()
+            .add_attribute("action", "accept_ownership")
+            .add_attribute("owner", info.sender))
     }
 
-    pub fn remove_owner(
+    pub fn cancel_owner_proposal(
         deps: DepsMut,
         info: MessageInfo,
         owner: String,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let mut state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_owner(deps.storage, &info.sender)?;
         let owner = deps.api.addr_validate(&owner)?;
-        assert!(
-            state.owners.iter().any(|x| x == owner),
-            "Owner does not exist"
-        );
+        if !PENDING_OWNERS.has(deps.storage, owner.clone()) {
+            return Err(ContractError::OwnerNotFound {});
+        }
+        PENDING_OWNERS.remove(deps.storage, owner);
+        Ok(Response::new().add_attribute("action", "cancel_owner_proposal"))
+    }
+
+    /// `State` without `owner`, checked against the minimum owners and the approval threshold.
+    fn remove_owner(deps: Deps, owner: &str) -> Result<State, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        let owner = deps.api.addr_validate(owner)?;
+        if !state.owners.contains(&owner) {
+            return Err(ContractError::OwnerNotFound {});
+        }
+        if state.owners.len() <= MIN_OWNERS {
+            return Err(ContractError::MinimumOwners {});
+        }
         state.owners.retain(|x| x != owner);
-        STATE.save(deps.storage, &state)?;
-        Ok(Response::new().add_attribute("action", "update_config"))
+        assert_multisig_setting(&state, &MULTISIG_SETTING.load(deps.storage)?)?;
+        Ok(state)
+    }
+
+    pub fn grant_role(
+        deps: DepsMut,
+        info: MessageInfo,
+        role: Role,
+        address: String,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
+        let address = deps.api.addr_validate(&address)?;
+        ROLES.update(deps.storage, address.clone(), |roles| -> StdResult<_> {
+            let mut roles = roles.unwrap_or_default();
+            if !roles.contains(&role) {
+                roles.push(role);
+            }
+            Ok(roles)
+        })?;
+        Ok(Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("address", address))
+    }
+
+    pub fn revoke_role(
+        deps: DepsMut,
+        info: MessageInfo,
+        role: Role,
+        address: String,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
+        let address = deps.api.addr_validate(&address)?;
+        let mut roles = ROLES
+            .may_load(deps.storage, address.clone())?
+            .unwrap_or_default();
+        if !roles.contains(&role) {
+            return Err(ContractError::RoleNotFound {});
+        }
+        roles.retain(|x| *x != role);
+        if roles.is_empty() {
+            ROLES.remove(deps.storage, address.clone());
+        } else {
+            ROLES.save(deps.storage, address.clone(), &roles)?;
+        }
+        Ok(Response::new()
+            .add_attribute("action", "revoke_role")
+            .add_attribute("address", address))
+    }
+
+    pub fn set_paused(
+        deps: DepsMut,
+        info: MessageInfo,
+        category: PauseCategory,
+        paused: bool,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        assert_role(deps.storage, &info.sender, Role::Pauser)?;
+        let mut pause_flags = PAUSE_FLAGS.may_load(deps.storage)?.unwrap_or_default();
+        pause_flags.set(category, paused);
+        PAUSE_FLAGS.save(deps.storage, &pause_flags)?;
+        Ok(Response::new()
+            .add_attribute("action", if paused { "pause" } else { "unpause" })
+            .add_attribute("category", format!("{category:?}")))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1174,11 +2047,8 @@ pub mod execute {
         amounts: Vec<Uint128>,
         nonce: Uint128,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        let state = assert_owner(deps.storage, &info.sender)?;
+        assert_not_paused(deps.storage, &[PauseCategory::Bridging])?;
         #[allow(deprecated)]
         let contract: Contract = Contract {
             constructor: None,
@@ -1221,8 +2091,8 @@ pub mod execute {
 
         let tokens = tokens
             .iter()
-            .map(|token| Token::Address(Address::from_str(token.as_str()).unwrap()))
-            .collect::<Vec<_>>();
+            .map(|token| Ok(Token::Address(evm_address(token)?)))
+            .collect::<Result<Vec<_>, ContractError>>()?;
         let amounts = amounts
             .iter()
             .map(|amount| Token::Uint(Uint::from_big_endian(&amount.to_be_bytes())))
@@ -1230,7 +2100,7 @@ pub mod execute {
 
         let tokens = &[
             Token::Array(tokens),
-            Token::Address(Address::from_str(to.as_str()).unwrap()),
+            Token::Address(evm_address(&to)?),
             Token::Array(amounts),
             Token::Uint(Uint::from_big_endian(&nonce.to_be_bytes())),
         ];
@@ -1280,11 +2150,7 @@ pub mod execute {
         info: MessageInfo,
         transaction_id: u64,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let state = STATE.load(deps.storage)?;
-        assert!(
-            state.owners.iter().any(|x| x == info.sender),
-            "Unauthorized"
-        );
+        assert_role(deps.storage, &info.sender, Role::Operator)?;
         Ok(Response::new()
             .add_message(CosmosMsg::Custom(PalomaMsg::SkywayMsg {
                 send_tx: None,
@@ -1296,7 +2162,7 @@ pub mod execute {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::State {} => to_json_binary(&STATE.load(deps.storage)?),
         QueryMsg::ChainSetting { chain_id } => {
@@ -1308,6 +2174,143 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 .unwrap_or_default();
             to_json_binary(&lp_balance)
         }
+        QueryMsg::LpBalances {
+            user,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let balances = LP_BALANCES
+                .prefix(user.clone())
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| {
+                    item.map(|(lp_token, amount)| LpBalanceInfo {
+                        user: user.clone(),
+                        lp_token,
+                        amount,
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            to_json_binary(&balances)
+        }
+        QueryMsg::LpHolders {
+            lp_token,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let holders = LP_BALANCES
+                .idx
+                .token
+                .prefix(lp_token.clone())
+                .range(
+                    deps.storage,
+                    start_after.map(|user| Bound::exclusive((user, lp_token))),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| {
+                    item.map(|((user, lp_token), amount)| LpBalanceInfo {
+                        user,
+                        lp_token,
+                        amount,
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            to_json_binary(&holders)
+        }
+        QueryMsg::Reconcile {
+            lp_token,
+            start_after,
+            limit,
+        } => to_json_binary(&reconcile(deps, env, lp_token, start_after, limit)?),
+        QueryMsg::LpTotals { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let totals = LP_TOTALS
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(lp_token, total)| LpTotalInfo { lp_token, total }))
+                .collect::<StdResult<Vec<_>>>()?;
+            to_json_binary(&totals)
+        }
+        QueryMsg::MultisigSetting {} => to_json_binary(&MULTISIG_SETTING.load(deps.storage)?),
+        QueryMsg::Proposal { proposal_id } => {
+            to_json_binary(&PROPOSALS.load(deps.storage, proposal_id)?)
+        }
+        QueryMsg::Proposals { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let proposals = PROPOSALS
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .filter(|item| {
+                    item.as_ref()
+                        .map_or(true, |(_, proposal)| proposal.expires_at > env.block.time)
+                })
+                .take(limit)
+                .map(|item| item.map(|(id, proposal)| ProposalInfo { id, proposal }))
+                .collect::<StdResult<Vec<_>>>()?;
+            to_json_binary(&proposals)
+        }
+        QueryMsg::PendingOwners {} => to_json_binary(
+            &PENDING_OWNERS
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        ),
+        QueryMsg::PauseFlags {} => {
+            to_json_binary(&PAUSE_FLAGS.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::Roles {} => {
+            let members = ROLES
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(address, roles)| RoleInfo { address, roles }))
+                .collect::<StdResult<Vec<_>>>()?;
+            to_json_binary(&RolesResponse {
+                owners: STATE.load(deps.storage)?.owners,
+                members,
+            })
+        }
+        QueryMsg::PriceGuard { pair } => {
+            to_json_binary(&PRICE_GUARDS.may_load(deps.storage, pair)?)
+        }
+        QueryMsg::SimulateExchange {
+            dex_router,
+            operations,
+            offer_amount,
+        } => to_json_binary(
+            &simulate_exchange(deps, dex_router, operations, offer_amount)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
+        QueryMsg::SimulateAddLiquidity {
+            pair,
+            coins,
+            slippage_tolerance,
+        } => to_json_binary(
+            &simulate_add_liquidity(deps, pair, coins, slippage_tolerance)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
+        QueryMsg::SimulateRemoveLiquidity { pair, amount } => {
+            let assets: Vec<Asset> = deps.querier.query_wasm_smart(
+                pair,
+                &ExternalQueryMsg::SimulateWithdraw { lp_amount: amount },
+            )?;
+            to_json_binary(&assets)
+        }
     }
 }
 
@@ -1333,11 +2336,11 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response<PalomaMsg>,
             gas_used: _,
             result:
                 SubMsgResult::Ok(SubMsgResponse {
-                    events: _,
+                    events,
                     data: _,
                     msg_responses: _,
                 }),
-        } => reply::execute_reply(deps, env, payload),
+        } => reply::execute_reply(deps, env, payload, &events),
         #[allow(deprecated)]
         Reply {
             id: ADD_LIQUIDITY_REPLY_ID,
@@ -1357,11 +2360,11 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response<PalomaMsg>,
             gas_used: _,
             result:
                 SubMsgResult::Ok(SubMsgResponse {
-                    events: _,
+                    events,
                     data: _,
                     msg_responses: _,
                 }),
-        } => reply::exchange_for_single_liqudity(deps, env, payload),
+        } => reply::exchange_for_single_liqudity(deps, env, payload, &events),
         #[allow(deprecated)]
         Reply {
             id: CHECK_PADEX_DIFF_REPLY_ID,
@@ -1373,93 +2376,665 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response<PalomaMsg>,
                     data: _,
                     msg_responses: _,
                 }),
-        } => reply::check_padex_diff(deps, payload),
+        } => reply::check_padex_diff(deps, env, payload),
+        #[allow(deprecated)]
+        Reply {
+            id: ZAP_OUT_REPLY_ID,
+            payload,
+            gas_used: _,
+            result:
+                SubMsgResult::Ok(SubMsgResponse {
+                    events,
+                    data: _,
+                    msg_responses: _,
+                }),
+        } => reply::zap_out_reply(deps, env, payload, &events),
+        Reply {
+            id: EXECUTE_REPLY_ID,
+            payload,
+            gas_used: _,
+            result: SubMsgResult::Err(error),
+        } => reply::exchange_failed(deps, env, payload, error),
+        Reply {
+            id: ADD_LIQUIDITY_REPLY_ID,
+            payload,
+            gas_used: _,
+            result: SubMsgResult::Err(error),
+        } => reply::add_liquidity_failed(deps, env, payload, error),
+        Reply {
+            id: EXECUTE_FOR_SINGLE_LIQUIDITY_REPLY_ID,
+            payload,
+            gas_used: _,
+            result: SubMsgResult::Err(error),
+        } => reply::zap_swap_failed(deps, env, payload, error),
         _ => Err(ContractError::UnknownReply {}),
     }
 }
 
+/// `Always` when a failed sub-message should be refunded instead of reverting the call.
+fn reply_on(refund_on_failure: bool) -> ReplyOn {
+    if refund_on_failure {
+        ReplyOn::Always
+    } else {
+        ReplyOn::Success
+    }
+}
+
+/// Balance of `asset_info` held by this contract.
+fn asset_balance(deps: Deps, env: &Env, asset_info: &AssetInfo) -> StdResult<Uint128> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } => Ok(deps
+            .querier
+            .query_balance(env.contract.address.clone(), denom)?
+            .amount),
+        AssetInfo::Token { contract_addr } => {
+            let balance: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+                contract_addr,
+                &cw20::Cw20QueryMsg::Balance {
+                    address: env.contract.address.to_string(),
+                },
+            )?;
+            Ok(balance.balance)
+        }
+    }
+}
+
+/// Balance of a cw20 contract address or native denom held by this contract.
+fn token_balance(deps: Deps, env: &Env, token: &str) -> StdResult<Uint128> {
+    if deps.api.addr_validate(token).is_ok() {
+        let balance: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+            token,
+            &cw20::Cw20QueryMsg::Balance {
+                address: env.contract.address.to_string(),
+            },
+        )?;
+        Ok(balance.balance)
+    } else {
+        Ok(deps
+            .querier
+            .query_balance(env.contract.address.clone(), token)?
+            .amount)
+    }
+}
+
+/// Fails with `Insolvent` unless the holdings of `token` left after sending `outflow`
+/// still cover every ledger balance. Call after the ledger has been debited.
+fn assert_solvent(
+    deps: Deps,
+    env: &Env,
+    token: &str,
+    outflow: Uint128,
+) -> Result<(), ContractError> {
+    let total = LP_TOTALS
+        .may_load(deps.storage, token.to_string())?
+        .unwrap_or_default();
+    let holdings = token_balance(deps, env, token)?;
+    if holdings < outflow || holdings - outflow < total {
+        return Err(ContractError::Insolvent {
+            token: token.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn reconcile(
+    deps: Deps,
+    env: Env,
+    lp_token: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReconcileResponse> {
+    let incentivizer = INCENTIVES_SETTING.load(deps.storage)?.incentivizer;
+    let internal_total = LP_TOTALS
+        .may_load(deps.storage, lp_token.clone())?
+        .unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut staked_balance = Uint128::zero();
+    let mut last_holder = None;
+    for item in LP_BALANCES
+        .idx
+        .token
+        .prefix(lp_token.clone())
+        .range(
+            deps.storage,
+            start_after.map(|user| Bound::exclusive((user, lp_token.clone()))),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+    {
+        let ((user, _), _) = item?;
+        let staked: Uint128 = deps.querier.query_wasm_smart(
+            incentivizer.clone(),
+            &IncentivizerQueryMsg::Deposit {
+                lp_token: lp_token.clone(),
+                user: user.clone(),
+            },
+        )?;
+        staked_balance += staked;
+        last_holder = Some(user);
+    }
+    let contract_balance = token_balance(deps, &env, &lp_token)?;
+    Ok(ReconcileResponse {
+        internal_total,
+        contract_balance,
+        staked_balance,
+        last_holder,
+        surplus: contract_balance.saturating_sub(internal_total),
+        deficit: internal_total.saturating_sub(contract_balance),
+    })
+}
+
+/// Simulates `Exchange`: router-only routes as one router call, routes with pair hops
+/// one hop at a time, as `ContinueRoute` executes them.
+fn simulate_exchange(
+    deps: Deps,
+    dex_router: Addr,
+    operations: Vec<SwapOperation>,
+    offer_amount: Uint128,
+) -> Result<SimulateExchangeResponse, ContractError> {
+    execute::validate_route(&operations)?;
+    if let AssetInfo::Token { .. } = operations[0].offer_asset_info() {
+        return Err(ContractError::UnsupportedCw20 {});
+    }
+    if offer_amount.is_zero() {
+        return Err(ContractError::FundsMismatch {});
+    }
+    let swaps = if operations
+        .iter()
+        .any(|x| matches!(x, SwapOperation::PairSwap { .. }))
+    {
+        let mut swaps = vec![];
+        let mut amount = offer_amount;
+        for operation in operations {
+            let swap = match &operation {
+                SwapOperation::PairSwap { pair, .. } => {
+                    simulate_pair_swap(deps, &pair.clone(), operation, amount)?
+                }
+                _ => simulate_router_swap(deps, &dex_router, vec![operation], amount)?,
+            };
+            amount = swap.return_amount;
+            swaps.push(swap);
+        }
+        swaps
+    } else {
+        vec![simulate_router_swap(
+            deps,
+            &dex_router,
+            operations,
+            offer_amount,
+        )?]
+    };
+    Ok(SimulateExchangeResponse {
+        return_amount: swaps.last().unwrap().return_amount,
+        swaps,
+    })
+}
+
+fn simulate_router_swap(
+    deps: Deps,
+    dex_router: &Addr,
+    operations: Vec<SwapOperation>,
+    offer_amount: Uint128,
+) -> StdResult<SwapSimulation> {
+    let response: SimulateSwapOperationsResponse = deps.querier.query_wasm_smart(
+        dex_router,
+        &ExternalQueryMsg::SimulateSwapOperations {
+            offer_amount,
+            operations: operations.clone(),
+        },
+    )?;
+    Ok(SwapSimulation {
+        operations,
+        offer_amount,
+        return_amount: response.amount,
+        commission_amount: None,
+        spread_amount: None,
+        price_impact: None,
+    })
+}
+
+fn simulate_pair_swap(
+    deps: Deps,
+    pair: &Addr,
+    operation: SwapOperation,
+    offer_amount: Uint128,
+) -> StdResult<SwapSimulation> {
+    let response: SimulationResponse = deps.querier.query_wasm_smart(
+        pair,
+        &ExternalQueryMsg::Simulation {
+            offer_asset: Asset {
+                info: operation.offer_asset_info(),
+                amount: offer_amount,
+            },
+            ask_asset_info: Some(operation.ask_asset_info()),
+        },
+    )?;
+    let gross = response.return_amount + response.spread_amount + response.commission_amount;
+    let price_impact = if gross.is_zero() {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(response.spread_amount, gross)
+    };
+    Ok(SwapSimulation {
+        operations: vec![operation],
+        offer_amount,
+        return_amount: response.return_amount,
+        commission_amount: Some(response.commission_amount),
+        spread_amount: Some(response.spread_amount),
+        price_impact: Some(price_impact),
+    })
+}
+
+/// Simulates `AddLiquidity` along the same branches, swapping through `zap_plan` when a
+/// single coin goes into a pair.
+fn simulate_add_liquidity(
+    deps: Deps,
+    pair: Addr,
+    coins: Vec<Coin>,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<SimulateAddLiquidityResponse, ContractError> {
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(pair.clone(), &ExternalQueryMsg::Pair {})?;
+    let (swap, coins) = if coins.len() == 2 {
+        (None, coins)
+    } else {
+        if coins.len() != 1 {
+            return Err(ContractError::UnsupportedCoinCount {});
+        }
+        let input_coin = &coins[0];
+        let (offer_asset_info, ask_denom, swap_amount) =
+            execute::zap_plan(deps, &pair, &pair_info.pair_type, input_coin)?;
+        let swap = simulate_pair_swap(
+            deps,
+            &pair,
+            SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: ask_denom.clone(),
+                },
+            },
+            swap_amount,
+        )?;
+        let coins = vec![
+            Coin::new(input_coin.amount - swap_amount, &input_coin.denom),
+            Coin::new(swap.return_amount, ask_denom),
+        ];
+        (Some(swap), coins)
+    };
+    let lp_amount: Uint128 = deps.querier.query_wasm_smart(
+        pair,
+        &ExternalQueryMsg::SimulateProvide {
+            assets: execute::provide_assets(&coins),
+            slippage_tolerance,
+        },
+    )?;
+    Ok(SimulateAddLiquidityResponse { swap, lp_amount })
+}
+
 pub mod reply {
-    use cosmwasm_std::{ReplyOn, SubMsg, Uint128, WasmMsg};
+    use cosmwasm_std::{BankMsg, Event, SubMsg, Uint128, WasmMsg};
     use cw20::{BalanceResponse, Cw20QueryMsg};
 
     use crate::{
         msg::{Asset, AssetInfo, ExternalExecuteMsg},
-        state::LP_BALANCES,
+        state::{credit_balance, debit_balance},
     };
 
+    use super::execute::{price_floor, provide_assets, provision_baseline};
     use super::*;
+    use std::str::FromStr;
 
     pub fn remove_liquidity(
         deps: DepsMut,
         env: Env,
         payload: Binary,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let (mut coins, receiver, chain_id, lp_token): (Vec<Coin>, String, String, String) =
-            from_json(payload)?;
-        coins[0].amount = deps
-            .querier
-            .query_balance(env.contract.address.clone(), coins[0].clone().denom)?
-            .amount
-            - coins[0].amount;
-        coins[1].amount = deps
+        let (
+            balances,
+            receiver,
+            chain_id,
+            lp_token,
+            mut min_assets_out,
+            pair,
+            receive_asset,
+            delivery,
+        ): RemovePayload = from_json(payload)?;
+        // A zap out floors the single coin it delivers rather than the withdrawn receive asset.
+        let receive_minimum = receive_asset
+            .as_ref()
+            .and_then(|denom| min_assets_out.iter().position(|min| &min.denom == denom))
+            .map(|i| min_assets_out.remove(i).amount);
+        let assets = balances
+            .into_iter()
+            .map(|before| {
+                Ok(Asset {
+                    amount: asset_balance(deps.as_ref(), &env, &before.info)?
+                        .checked_sub(before.amount)?,
+                    info: before.info,
+                })
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+        for minimum in min_assets_out {
+            let received = assets
+                .iter()
+                .find(|asset| asset.info.to_string() == minimum.denom)
+                .map(|asset| asset.amount)
+                .unwrap_or_default();
+            if received < minimum.amount {
+                return Err(ContractError::NotEnoughAssetsOut {
+                    denom: minimum.denom,
+                    minimum: minimum.amount,
+                    received,
+                });
+            }
+        }
+        let mut coins = vec![];
+        let mut response = Response::new().add_attribute("lp_token", lp_token.clone());
+        for (i, asset) in assets.iter().enumerate() {
+            response = response.add_attribute(format!("coin{i}"), asset.to_string());
+            match &asset.info {
+                AssetInfo::NativeToken { denom } => coins.push(Coin::new(asset.amount, denom)),
+                // Skyway only bridges native denoms, so cw20 output stays in the ledger
+                AssetInfo::Token { contract_addr } if !asset.amount.is_zero() => {
+                    credit_balance(
+                        deps.storage,
+                        &receiver,
+                        contract_addr.as_str(),
+                        asset.amount,
+                    )?;
+                }
+                AssetInfo::Token { .. } => {}
+            }
+        }
+        if let Some(denom) = receive_asset {
+            // `remove_liquidity` only zaps out of pairs of two native assets.
+            let (kept, offered): (Vec<Coin>, Vec<Coin>) =
+                coins.into_iter().partition(|coin| coin.denom == denom);
+            let (Some(kept), Some(offered)) = (kept.first(), offered.first()) else {
+                return Err(ContractError::UnsupportedZapOut {});
+            };
+            return zap_out(
+                deps,
+                env,
+                Addr::unchecked(pair),
+                receiver,
+                chain_id,
+                delivery,
+                lp_token,
+                kept.clone(),
+                offered.clone(),
+                receive_minimum,
+            );
+        }
+        let messages = deliver(deps, delivery, receiver, chain_id, coins)?;
+        Ok(response
+            .add_messages(messages)
+            .add_attribute("action", "remove_liquidity"))
+    }
+
+    /// `(balances, receiver, chain_id, lp_token, min_assets_out, pair, receive_asset,
+    /// delivery)` carried by the withdrawal, `balances` being the pair's assets held before it
+    type RemovePayload = (
+        Vec<Asset>,
+        String,
+        String,
+        String,
+        Vec<Coin>,
+        String,
+        Option<String>,
+        Delivery,
+    );
+
+    /// Hands native `coins` to `receiver` as `delivery` asks. Bridging keeps zero amounts
+    /// like before; sends and credits skip them.
+    fn deliver(
+        deps: DepsMut,
+        delivery: Delivery,
+        receiver: String,
+        chain_id: String,
+        coins: Vec<Coin>,
+    ) -> Result<Vec<CosmosMsg<PalomaMsg>>, ContractError> {
+        match delivery {
+            Delivery::Bridge => Ok(coins
+                .into_iter()
+                .map(|coin| {
+                    CosmosMsg::Custom(PalomaMsg::SkywayMsg {
+                        send_tx: Some(SendTx {
+                            remote_chain_destination_address: receiver.clone(),
+                            amount: coin.to_string(),
+                            chain_reference_id: chain_id.clone(),
+                        }),
+                        cancel_tx: None,
+                    })
+                })
+                .collect()),
+            Delivery::Send { address } => {
+                let amount: Vec<Coin> = coins
+                    .into_iter()
+                    .filter(|coin| !coin.amount.is_zero())
+                    .collect();
+                if amount.is_empty() {
+                    return Ok(vec![]);
+                }
+                Ok(vec![CosmosMsg::Bank(BankMsg::Send {
+                    to_address: address,
+                    amount,
+                })])
+            }
+            Delivery::Credit => {
+                for coin in coins.into_iter().filter(|coin| !coin.amount.is_zero()) {
+                    credit_balance(deps.storage, &receiver, &coin.denom, coin.amount)?;
+                }
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Swaps `offered` into the denom of `kept` through `pair` so a single coin is delivered,
+    /// floored by the pair's price guard like a route hop and by `minimum` once swapped.
+    #[allow(clippy::too_many_arguments)]
+    fn zap_out(
+        mut deps: DepsMut,
+        env: Env,
+        pair: Addr,
+        receiver: String,
+        chain_id: String,
+        delivery: Delivery,
+        lp_token: String,
+        kept: Coin,
+        offered: Coin,
+        minimum: Option<Uint128>,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        if offered.amount.is_zero() {
+            assert_coin_out(&kept, minimum)?;
+            let messages = deliver(deps, delivery, receiver, chain_id, vec![kept.clone()])?;
+            return Ok(Response::new()
+                .add_messages(messages)
+                .add_attribute("lp_token", lp_token)
+                .add_attribute("coin_out", kept.to_string())
+                .add_attribute("action", "remove_liquidity"));
+        }
+        let offer_asset_info = AssetInfo::NativeToken {
+            denom: offered.denom.clone(),
+        };
+        let ask_asset_info = AssetInfo::NativeToken {
+            denom: kept.denom.clone(),
+        };
+        let floor = price_floor(
+            deps.branch(),
+            &pair,
+            &offer_asset_info,
+            &ask_asset_info,
+            offered.amount,
+        )?;
+        let ask_balance = deps
             .querier
-            .query_balance(env.contract.address.clone(), coins[1].clone().denom)?
-            .amount
-            - coins[1].amount;
+            .query_balance(&env.contract.address, &kept.denom)?
+            .amount;
+        let payload = to_json_binary(&(
+            receiver,
+            chain_id,
+            delivery,
+            kept,
+            ask_balance,
+            floor,
+            minimum,
+        ))?;
         Ok(Response::new()
-            .add_messages(vec![
-                CosmosMsg::Custom(PalomaMsg::SkywayMsg {
-                    send_tx: Some(SendTx {
-                        remote_chain_destination_address: receiver.clone(),
-                        amount: coins[0].to_string(),
-                        chain_reference_id: chain_id.clone(),
-                    }),
-                    cancel_tx: None,
-                }),
-                CosmosMsg::Custom(PalomaMsg::SkywayMsg {
-                    send_tx: Some(SendTx {
-                        remote_chain_destination_address: receiver,
-                        amount: coins[1].to_string(),
-                        chain_reference_id: chain_id,
-                    }),
-                    cancel_tx: None,
+            .add_submessage(SubMsg {
+                id: ZAP_OUT_REPLY_ID,
+                msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: pair.to_string(),
+                    msg: to_json_binary(&ExternalExecuteMsg::ExecuteSwapOperations {
+                        operations: vec![SwapOperation::AstroSwap {
+                            offer_asset_info,
+                            ask_asset_info,
+                        }],
+                        minimum_receive: floor,
+                        to: None,
+                        max_spread: None,
+                    })?,
+                    funds: vec![offered.clone()],
                 }),
-            ])
+                payload,
+                gas_limit: None,
+                reply_on: ReplyOn::Success,
+            })
             .add_attribute("lp_token", lp_token)
-            .add_attribute("coin0", coins[0].to_string())
-            .add_attribute("coin1", coins[1].to_string())
+            .add_attribute("offered", offered.to_string())
             .add_attribute("action", "remove_liquidity"))
     }
 
-    pub fn execute_reply(
+    pub fn zap_out_reply(
         deps: DepsMut,
         env: Env,
         payload: Binary,
+        events: &[Event],
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let (recipient, chain_id, coin): (String, String, Coin) = from_json(payload)?;
-        let mut increased_coin = deps
-            .querier
-            .query_balance(env.contract.address.clone(), coin.denom.clone())?;
-        increased_coin.amount -= coin.amount;
-        assert!(!increased_coin.amount.is_zero(), "Not enough output coin");
+        let (receiver, chain_id, delivery, kept, ask_balance, floor, minimum): (
+            String,
+            String,
+            Delivery,
+            Coin,
+            Uint128,
+            Option<Uint128>,
+            Option<Uint128>,
+        ) = from_json(payload)?;
+        let output = swap_output(
+            events,
+            deps.querier
+                .query_balance(&env.contract.address, &kept.denom)?
+                .amount
+                .checked_sub(ask_balance)?,
+        )?;
+        if output < floor.unwrap_or_default() {
+            return Err(ContractError::PriceDeviation {});
+        }
+        let coin_out = Coin::new(kept.amount.checked_add(output)?, kept.denom);
+        assert_coin_out(&coin_out, minimum)?;
+        let messages = deliver(deps, delivery, receiver, chain_id, vec![coin_out.clone()])?;
         Ok(Response::new()
-            .add_message(CosmosMsg::Custom(PalomaMsg::SkywayMsg {
-                send_tx: Some(SendTx {
-                    remote_chain_destination_address: recipient,
-                    amount: increased_coin.to_string(),
-                    chain_reference_id: chain_id,
-                }),
-                cancel_tx: None,
-            }))
-            .add_attribute("coin_out", increased_coin.to_string())
-            .add_attribute("action", "execute_reply"))
+            .add_messages(messages)
+            .add_attribute("swapped", output)
+            .add_attribute("coin_out", coin_out.to_string())
+            .add_attribute("action", "zap_out_reply"))
+    }
+
+    /// Fails with `NotEnoughAssetsOut` when the single coin of a zap out is below `minimum`.
+    fn assert_coin_out(coin_out: &Coin, minimum: Option<Uint128>) -> Result<(), ContractError> {
+        match minimum {
+            Some(minimum) if coin_out.amount < minimum => Err(ContractError::NotEnoughAssetsOut {
+                denom: coin_out.denom.clone(),
+                minimum,
+                received: coin_out.amount,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// `return_amount` of the last swap in `events`, which is the output of the final hop.
+    fn reported_return(events: &[Event]) -> Result<Option<Uint128>, ContractError> {
+        events
+            .iter()
+            .rev()
+            .filter(|event| event.ty == "wasm")
+            .flat_map(|event| event.attributes.iter().rev())
+            .find(|attr| attr.key == "return_amount")
+            .map(|attr| Uint128::from_str(&attr.value))
+            .transpose()
+            .map_err(ContractError::from)
+    }
+
+    /// Output of a swap as reported by its events, checked against the balance growth.
+    /// Falls back to the balance growth when no swap event carries a `return_amount`.
+    fn swap_output(events: &[Event], measured: Uint128) -> Result<Uint128, ContractError> {
+        match reported_return(events)? {
+            Some(reported) if reported != measured => {
+                Err(ContractError::OutputMismatch { reported, measured })
+            }
+            _ => Ok(measured),
+        }
+    }
+
+    /// `(recipient, chain_id, asset, refund, delivery, floor)` carried by the swap,
+    /// `asset` being the ask asset balance before it and `floor` the price guard floor of a
+    /// router-only route
+    type ExchangePayload = (
+        String,
+        String,
+        Asset,
+        Option<Coin>,
+        Delivery,
+        Option<Uint128>,
+    );
+
+    pub fn execute_reply(
+        deps: DepsMut,
+        env: Env,
+        payload: Binary,
+        events: &[Event],
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        let (recipient, chain_id, asset, _, delivery, floor): ExchangePayload = from_json(payload)?;
+        let amount = swap_output(
+            events,
+            asset_balance(deps.as_ref(), &env, &asset.info)?.checked_sub(asset.amount)?,
+        )?;
+        if amount.is_zero() {
+            return Err(ContractError::NotEnoughOutputCoin {});
+        }
+        if amount < floor.unwrap_or_default() {
+            return Err(ContractError::PriceDeviation {});
+        }
+        match asset.info {
+            AssetInfo::NativeToken { denom } => {
+                let coin_out = Coin::new(amount, denom);
+                let messages =
+                    deliver(deps, delivery, recipient, chain_id, vec![coin_out.clone()])?;
+                Ok(Response::new()
+                    .add_messages(messages)
+                    .add_attribute("coin_out", coin_out.to_string())
+                    .add_attribute("action", "execute_reply"))
+            }
+            // Skyway only bridges native denoms, so cw20 output stays in the ledger
+            AssetInfo::Token { contract_addr } => {
+                credit_balance(deps.storage, &recipient, contract_addr.as_str(), amount)?;
+                Ok(Response::new()
+                    .add_attribute("token_out", contract_addr)
+                    .add_attribute("credited", amount)
+                    .add_attribute("recipient", recipient)
+                    .add_attribute("action", "execute_reply"))
+            }
+        }
     }
     pub fn add_liquidity(
         deps: DepsMut,
         env: Env,
         payload: Binary,
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let (depositor, lp_token, init_lp_balance): (String, String, Uint128) = from_json(payload)?;
+        let (depositor, lp_token, init_lp_balance, _, min_lp_out, baseline): ProvidePayload =
+            from_json(payload)?;
         let result_lp_balance: BalanceResponse = deps.querier.query_wasm_smart(
             lp_token.clone(),
             &Cw20QueryMsg::Balance {
@@ -1467,108 +3042,213 @@ pub mod reply {
             },
         )?;
         let lp_amount = result_lp_balance.balance - init_lp_balance;
-        LP_BALANCES.update(
-            deps.storage,
-            (depositor.clone(), lp_token.clone()),
-            |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + lp_amount) },
-        )?;
+        if lp_amount < min_lp_out.unwrap_or_default() {
+            return Err(ContractError::NotEnoughLpOut {});
+        }
+        credit_balance(deps.storage, &depositor, &lp_token, lp_amount)?;
+        // Whatever the pair did not take goes back to the depositor, withdrawable with `send_to_evm`.
+        let mut leftovers = vec![];
+        for expected in baseline {
+            let balance = deps
+                .querier
+                .query_balance(&env.contract.address, &expected.denom)?;
+            let leftover = balance.amount.checked_sub(expected.amount)?;
+            if leftover.is_zero() {
+                continue;
+            }
+            credit_balance(deps.storage, &depositor, &expected.denom, leftover)?;
+            leftovers.push(Coin::new(leftover, expected.denom));
+        }
+        let leftovers = leftovers
+            .iter()
+            .map(Coin::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
         Ok(Response::new()
             .add_attribute("lp_token", lp_token)
             .add_attribute("lp_amount", lp_amount)
+            .add_attribute("leftovers", leftovers)
             .add_attribute("action", "add_liquidity"))
     }
 
+    /// `(depositor, lp_token, init_lp_balance, refund, min_lp_out, baseline)` carried by the
+    /// provision, `baseline` coming from `provision_baseline`
+    type ProvidePayload = (
+        String,
+        String,
+        Uint128,
+        Option<Vec<Coin>>,
+        Option<Uint128>,
+        Vec<Coin>,
+    );
+
+    /// `(pair, depositor, lp_token, init_lp_balance, kept, ask_balance, refund,
+    /// slippage_tolerance, min_lp_out)` carried by the zap swap, where `kept` is the
+    /// input left to provide and `ask_balance` the contract's ask denom balance before the swap
+    type ZapPayload = (
+        String,
+        String,
+        String,
+        Uint128,
+        Coin,
+        Coin,
+        Option<Coin>,
+        Option<Decimal>,
+        Option<Uint128>,
+    );
+
     pub fn exchange_for_single_liqudity(
         deps: DepsMut,
         env: Env,
         payload: Binary,
+        events: &[Event],
     ) -> Result<Response<PalomaMsg>, ContractError> {
-        let (pair, depositor, lp_token, init_lp_balance, coins): (
-            String,
-            String,
-            String,
-            Uint128,
-            Vec<Coin>,
-        ) = from_json(payload)?;
+        let (
+            pair,
+            depositor,
+            lp_token,
+            init_lp_balance,
+            kept,
+            ask_balance,
+            refund,
+            slippage_tolerance,
+            min_lp_out,
+        ): ZapPayload = from_json(payload)?;
         let output_coin = Coin {
-            denom: coins[1].denom.clone(),
-            amount: deps
-                .querier
-                .query_balance(&env.contract.address, coins[1].denom.clone())?
-                .amount
-                - coins[1].amount,
+            amount: swap_output(
+                events,
+                deps.querier
+                    .query_balance(&env.contract.address, &ask_balance.denom)?
+                    .amount
+                    .checked_sub(ask_balance.amount)?,
+            )?,
+            denom: ask_balance.denom,
         };
-        assert!(!output_coin.amount.is_zero(), "Not enough output coin");
-        let payload = to_json_binary(&(depositor, lp_token, init_lp_balance))?;
-        let coins = vec![
-            Coin {
-                denom: coins[0].denom.clone(),
-                amount: coins[0].amount,
-            },
-            Coin {
-                denom: output_coin.denom.clone(),
-                amount: output_coin.amount,
-            },
-        ];
+        if output_coin.amount.is_zero() {
+            return Err(ContractError::NotEnoughOutputCoin {});
+        }
+        let coins = vec![kept, output_coin];
+        // Once swapped, a failed provision refunds both provided coins.
+        let payload = to_json_binary(&(
+            depositor,
+            lp_token,
+            init_lp_balance,
+            refund.as_ref().map(|_| coins.clone()),
+            min_lp_out,
+            provision_baseline(deps.as_ref(), &env, &coins)?,
+        ))?;
         Ok(Response::new()
             .add_submessage(SubMsg {
                 id: ADD_LIQUIDITY_REPLY_ID,
                 msg: CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr: pair.to_string(),
                     msg: to_json_binary(&ExternalExecuteMsg::ProvideLiquidity {
-                        assets: coins
-                            .iter()
-                            .map(|coin| Asset {
-                                info: AssetInfo::NativeToken {
-                                    denom: coin.denom.clone(),
-                                },
-                                amount: coin.amount,
-                            })
-                            .collect(),
-                        slippage_tolerance: None,
+                        assets: provide_assets(&coins),
+                        slippage_tolerance,
                         receiver: None,
                     })?,
                     funds: coins.clone(),
                 }),
                 payload,
                 gas_limit: None,
-                reply_on: ReplyOn::Success,
+                reply_on: reply_on(refund.is_some()),
             })
             .add_attribute("coin0", coins[0].to_string())
             .add_attribute("coin1", coins[1].to_string())
             .add_attribute("action", "exchange_for_single_liqudity"))
     }
+
+    /// Credits `coins` to `user`'s ledger balance after a failed sub-message and reports
+    /// the error in an `operation_failed` event.
+    fn credit_refund(
+        deps: DepsMut,
+        env: Env,
+        operation: &str,
+        user: String,
+        coins: Vec<Coin>,
+        error: String,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        for coin in &coins {
+            credit_balance(deps.storage, &user, &coin.denom, coin.amount)?;
+            // Never credit coins that did not come back, e.g. when sending them failed.
+            assert_solvent(deps.as_ref(), &env, &coin.denom, Uint128::zero())?;
+        }
+        let refunded = coins
+            .iter()
+            .map(Coin::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        Ok(Response::new()
+            .add_event(
+                Event::new("operation_failed")
+                    .add_attribute("operation", operation)
+                    .add_attribute("user", user)
+                    .add_attribute("refunded", refunded)
+                    .add_attribute("error", error),
+            )
+            .add_attribute("action", "refund"))
+    }
+
+    pub fn exchange_failed(
+        deps: DepsMut,
+        env: Env,
+        payload: Binary,
+        error: String,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        let (recipient, _, _, refund, _, _): ExchangePayload = from_json(payload)?;
+        let refund = refund.ok_or(ContractError::UnknownReply {})?;
+        credit_refund(deps, env, "exchange", recipient, vec![refund], error)
+    }
+
+    pub fn add_liquidity_failed(
+        deps: DepsMut,
+        env: Env,
+        payload: Binary,
+        error: String,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        let (depositor, _, _, refund, _, _): ProvidePayload = from_json(payload)?;
+        let refund = refund.ok_or(ContractError::UnknownReply {})?;
+        credit_refund(deps, env, "add_liquidity", depositor, refund, error)
+    }
+
+    pub fn zap_swap_failed(
+        deps: DepsMut,
+        env: Env,
+        payload: Binary,
+        error: String,
+    ) -> Result<Response<PalomaMsg>, ContractError> {
+        let (_, depositor, _, _, _, _, refund, _, _): ZapPayload = from_json(payload)?;
+        let refund = refund.ok_or(ContractError::UnknownReply {})?;
+        credit_refund(deps, env, "add_liquidity", depositor, vec![refund], error)
+    }
+
+    /// Attributes the change of the contract's own PADEX balance across the submessage
+    /// (rewards paid out, locks funded or unlocked) to `user` in the ledger.
     pub fn check_padex_diff(
         deps: DepsMut,
+        env: Env,
         payload: Binary,
     ) -> Result<Response<PalomaMsg>, ContractError> {
         let (user, padex_balance): (String, Coin) = from_json(payload)?;
-        let new_balance = deps.querier.query_balance(
-            deps.api.addr_validate(user.as_str())?,
-            padex_balance.denom.clone(),
-        )?;
-        if new_balance.amount > padex_balance.amount {
-            let diff_balance = new_balance.amount - padex_balance.amount;
-            if !diff_balance.is_zero() {
-                LP_BALANCES.update(
-                    deps.storage,
-                    (user.clone(), padex_balance.denom.clone()),
-                    |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + diff_balance) },
-                )?;
+        let new_balance = deps
+            .querier
+            .query_balance(env.contract.address, padex_balance.denom.clone())?;
+        let event = Event::new("padex_diff")
+            .add_attribute("user", &user)
+            .add_attribute("denom", padex_balance.denom.clone());
+        let event = if new_balance.amount >= padex_balance.amount {
+            let credited = new_balance.amount.checked_sub(padex_balance.amount)?;
+            if !credited.is_zero() {
+                credit_balance(deps.storage, &user, &padex_balance.denom, credited)?;
             }
+            event.add_attribute("credited", credited)
         } else {
-            let diff_balance = padex_balance.amount - new_balance.amount;
-            if !diff_balance.is_zero() {
-                LP_BALANCES.update(
-                    deps.storage,
-                    (user.clone(), padex_balance.denom.clone()),
-                    |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() - diff_balance) },
-                )?;
-            }
-        }
+            let debited = padex_balance.amount.checked_sub(new_balance.amount)?;
+            debit_balance(deps.storage, &user, &padex_balance.denom, debited)?;
+            event.add_attribute("debited", debited)
+        };
         Ok(Response::new()
-            .add_attribute("action", "claim_rewards")
-            .add_attribute("user", user)
-            .add_attribute("padex_balance", padex_balance.to_string()))
+            .add_event(event)
+            .add_attribute("action", "check_padex_diff"))
     }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,9 +6,39 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Owner does not exist")]
+    OwnerNotFound {},
+
+    #[error("At least one owner is required")]
+    MinimumOwners {},
+
+    #[error("Role not granted")]
+    RoleNotFound {},
+
+    #[error("Operation is paused")]
+    Paused {},
+
+    #[error("Approval threshold must be between 1 and the number of owners")]
+    InvalidThreshold {},
+
+    #[error("Proposal TTL must be greater than zero")]
+    InvalidProposalTtl {},
+
+    #[error("Proposal not found")]
+    ProposalNotFound {},
+
+    #[error("Proposal expired")]
+    ProposalExpired {},
+
+    #[error("Proposal already approved by sender")]
+    AlreadyApproved {},
+
     #[error("Pending")]
     Pending {},
 
@@ -18,6 +48,57 @@ pub enum ContractError {
     #[error("CW20 is not supported")]
     UnsupportedCw20 {},
 
+    #[error("Only 1 or 2 coins are supported")]
+    UnsupportedCoinCount {},
+
+    #[error("Invalid EVM address: {address}")]
+    InvalidEvmAddress { address: String },
+
+    #[error("Insufficient balance")]
+    InsufficientBalance {},
+
+    #[error("Holdings of {token} do not cover ledger balances")]
+    Insolvent { token: String },
+
+    #[error(
+        "Offer must be a single non-zero coin of the first offer asset, via funds or offer_amount"
+    )]
+    FundsMismatch {},
+
+    #[error("Swap route is empty")]
+    EmptyRoute {},
+
+    #[error("Invalid swap route at hop {hop}")]
+    InvalidRoute { hop: usize },
+
+    #[error("Swap output deviates from the guarded price by more than the allowed maximum")]
+    PriceDeviation {},
+
+    #[error("Swap reported {reported} but the balance grew by {measured}")]
+    OutputMismatch {
+        reported: Uint128,
+        measured: Uint128,
+    },
+
+    #[error("Minted LP tokens are below min_lp_out")]
+    NotEnoughLpOut {},
+
+    #[error("Withdrew {received} {denom}, below the minimum of {minimum}")]
+    NotEnoughAssetsOut {
+        denom: String,
+        minimum: Uint128,
+        received: Uint128,
+    },
+
+    #[error("{denom} is not a native asset of the pair")]
+    InvalidReceiveAsset { denom: String },
+
+    #[error("Zapping out needs a pair of two native assets")]
+    UnsupportedZapOut {},
+
+    #[error("Not enough output coin")]
+    NotEnoughOutputCoin {},
+
     #[error("Insufficient liquidity")]
     InsufficientLiquidity {}, // Add any other custom errors you like here.
                               // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
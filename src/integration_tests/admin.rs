@@ -0,0 +1,564 @@
+use cosmwasm_std::{Addr, Binary, Uint128, Uint256};
+use cw_multi_test::Executor;
+use ethabi::{encode, short_signature, Address, ParamType, Token, Uint};
+use std::str::FromStr;
+
+use super::*;
+use crate::msg::{CancelTx, ExecuteJob, RoleInfo, RolesResponse};
+use crate::state::{ChainSetting, Role, State};
+
+const EVM_ADDRESS: &str = "0x2222222222222222222222222222222222222222";
+
+fn scheduled(function: &str, params: &[ParamType], tokens: &[Token]) -> PalomaMsg {
+    let mut payload = short_signature(function, params).to_vec();
+    payload.extend(encode(tokens));
+    PalomaMsg::SchedulerMsg {
+        execute_job: ExecuteJob {
+            job_id: "main_job".to_string(),
+            payload: Binary::new(payload),
+        },
+    }
+}
+
+#[test]
+fn instantiate_stores_owners_and_config() {
+    let suite = Suite::new();
+    let state: State = suite.query(&QueryMsg::State {});
+    assert_eq!(state.owners, vec![suite.owner.clone()]);
+    assert_eq!(state.retry_delay, 60);
+}
+
+#[test]
+fn migrate_replaces_incentives_setting() {
+    let mut suite = Suite::new();
+    let code_id = suite.app.contract_data(&suite.trader).unwrap().code_id;
+    let res = suite
+        .app
+        .migrate_contract(
+            suite.owner.clone(),
+            suite.trader.clone(),
+            &MigrateMsg {
+                incentivizer: suite.incentivizer.clone(),
+                padex: PADEX.to_string(),
+                vepades: VEPADEX.to_string(),
+                approval_threshold: 1,
+                proposal_ttl: PROPOSAL_TTL,
+            },
+            code_id,
+        )
+        .unwrap();
+    assert!(res.has_event(&cosmwasm_std::Event::new("wasm").add_attribute("action", "migrate")));
+}
+
+#[test]
+fn set_chain_setting() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let setting: ChainSetting = suite.query(&QueryMsg::ChainSetting {
+        chain_id: CHAIN_ID.to_string(),
+    });
+    assert_eq!(
+        setting,
+        ChainSetting {
+            compass_job_id: "compass_job".to_string(),
+            main_job_id: "main_job".to_string(),
+        }
+    );
+}
+
+#[test]
+fn compass_admin_jobs_are_scheduled() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let evm_address = Address::from_str(EVM_ADDRESS).unwrap();
+
+    suite
+        .execute_as_owner(ExecuteMsg::SetPaloma {
+            chain_id: CHAIN_ID.to_string(),
+        })
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::UpdateRefundWallet {
+            chain_id: CHAIN_ID.to_string(),
+            new_refund_wallet: EVM_ADDRESS.to_string(),
+        })
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::UpdateGasFee {
+            chain_id: CHAIN_ID.to_string(),
+            new_gas_fee: Uint256::from(10u8),
+        })
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::UpdateServiceFeeCollector {
+            chain_id: CHAIN_ID.to_string(),
+            new_service_fee_collector: EVM_ADDRESS.to_string(),
+        })
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::UpdateServiceFee {
+            chain_id: CHAIN_ID.to_string(),
+            new_service_fee: Uint256::from(20u8),
+        })
+        .unwrap();
+
+    assert_eq!(
+        suite.take_paloma_msgs(),
+        vec![
+            scheduled("set_paloma", &[], &[]),
+            scheduled(
+                "update_refund_wallet",
+                &[ParamType::Address],
+                &[Token::Address(evm_address)]
+            ),
+            scheduled(
+                "update_gas_fee",
+                &[ParamType::Uint(256)],
+                &[Token::Uint(Uint::from(10))]
+            ),
+            scheduled(
+                "update_service_fee_collector",
+                &[ParamType::Address],
+                &[Token::Address(evm_address)]
+            ),
+            scheduled(
+                "update_service_fee",
+                &[ParamType::Uint(256)],
+                &[Token::Uint(Uint::from(20))]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn compass_admin_job_requires_chain_setting() {
+    let mut suite = Suite::new();
+    suite
+        .execute_as_owner(ExecuteMsg::SetPaloma {
+            chain_id: CHAIN_ID.to_string(),
+        })
+        .unwrap_err();
+    assert!(suite.take_paloma_msgs().is_empty());
+}
+
+#[test]
+fn send_token_respects_retry_delay() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let send_token = ExecuteMsg::SendToken {
+        chain_id: CHAIN_ID.to_string(),
+        tokens: vec![EVM_ADDRESS.to_string()],
+        to: EVM_RECEIVER.to_string(),
+        amounts: vec![Uint128::new(5)],
+        nonce: Uint128::one(),
+    };
+
+    suite.execute_as_owner(send_token.clone()).unwrap();
+    assert_eq!(
+        suite.take_paloma_msgs(),
+        vec![scheduled(
+            "send_token",
+            &[
+                ParamType::Array(Box::new(ParamType::Address)),
+                ParamType::Address,
+                ParamType::Array(Box::new(ParamType::Uint(256))),
+                ParamType::Uint(256),
+            ],
+            &[
+                Token::Array(vec![Token::Address(
+                    Address::from_str(EVM_ADDRESS).unwrap()
+                )]),
+                Token::Address(Address::from_str(EVM_RECEIVER).unwrap()),
+                Token::Array(vec![Token::Uint(Uint::from(5))]),
+                Token::Uint(Uint::from(1)),
+            ]
+        )]
+    );
+
+    let err = suite.execute_as_owner(send_token.clone()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Pending {})
+    ));
+
+    suite
+        .app
+        .update_block(|block| block.time = block.time.plus_seconds(61));
+    suite.execute_as_owner(send_token).unwrap();
+    assert_eq!(suite.take_paloma_msgs().len(), 1);
+}
+
+#[test]
+fn cancel_tx_emits_skyway_cancel() {
+    let mut suite = Suite::new();
+    suite
+        .execute_as_owner(ExecuteMsg::CancelTx { transaction_id: 7 })
+        .unwrap();
+    assert_eq!(
+        suite.take_paloma_msgs(),
+        vec![PalomaMsg::SkywayMsg {
+            send_tx: None,
+            cancel_tx: Some(CancelTx { transaction_id: 7 }),
+        }]
+    );
+}
+
+#[test]
+fn update_config_and_owners() {
+    let mut suite = Suite::new();
+    let new_owner = suite.app.api().addr_make("new_owner");
+
+    suite
+        .execute_as_owner(ExecuteMsg::UpdateConfig {
+            retry_delay: Some(120),
+            proposal_ttl: None,
+        })
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::ProposeOwner {
+            owners: vec![new_owner.to_string()],
+        })
+        .unwrap();
+    let state: State = suite.query(&QueryMsg::State {});
+    assert_eq!(state.retry_delay, 120);
+    assert_eq!(state.owners, vec![suite.owner.clone()]);
+    let pending: Vec<Addr> = suite.query(&QueryMsg::PendingOwners {});
+    assert_eq!(pending, vec![new_owner.clone()]);
+
+    suite
+        .execute(&new_owner, ExecuteMsg::AcceptOwnership {})
+        .unwrap();
+    let state: State = suite.query(&QueryMsg::State {});
+    assert_eq!(state.owners, vec![suite.owner.clone(), new_owner.clone()]);
+    let pending: Vec<Addr> = suite.query(&QueryMsg::PendingOwners {});
+    assert!(pending.is_empty());
+
+    let res = suite
+        .execute(
+            &new_owner,
+            ExecuteMsg::RemoveOwner {
+                owner: suite.owner.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &cosmwasm_std::Event::new("wasm")
+            .add_attribute("executed", "remove_owner")
+            .add_attribute("owner", suite.owner.to_string())
+    ));
+    let state: State = suite.query(&QueryMsg::State {});
+    assert_eq!(state.owners, vec![new_owner.clone()]);
+
+    let err = suite
+        .execute(
+            &new_owner,
+            ExecuteMsg::RemoveOwner {
+                owner: new_owner.to_string(),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::MinimumOwners {})
+    ));
+}
+
+#[test]
+fn only_proposed_owner_can_accept() {
+    let mut suite = Suite::new();
+    let proposed = suite.app.api().addr_make("proposed");
+    let user = suite.user.clone();
+    suite
+        .execute_as_owner(ExecuteMsg::ProposeOwner {
+            owners: vec![proposed.to_string()],
+        })
+        .unwrap();
+
+    let err = suite
+        .execute(&user, ExecuteMsg::AcceptOwnership {})
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    suite
+        .execute_as_owner(ExecuteMsg::CancelOwnerProposal {
+            owner: proposed.to_string(),
+        })
+        .unwrap();
+    let err = suite
+        .execute(&proposed, ExecuteMsg::AcceptOwnership {})
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+    let state: State = suite.query(&QueryMsg::State {});
+    assert_eq!(state.owners, vec![suite.owner.clone()]);
+}
+
+#[test]
+fn add_lp_token_credits_ledger() {
+    let mut suite = Suite::new();
+    let lp_token = suite.lp_token.to_string();
+    suite.mint_lp_to_trader(15);
+    suite.credit(EVM_RECEIVER, &lp_token, 10);
+    suite.credit(EVM_RECEIVER, &lp_token, 5);
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(15));
+
+    // Credits beyond what the contract holds are rejected.
+    let err = suite
+        .execute_as_owner(ExecuteMsg::AddLpToken {
+            lp_token: lp_token.clone(),
+            user: EVM_RECEIVER.to_string(),
+            amount: Uint128::one(),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Insolvent { token }) if *token == lp_token
+    ));
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(15));
+}
+
+#[test]
+fn non_owner_cannot_execute() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let user = suite.user.clone();
+    let msgs = vec![
+        ExecuteMsg::CancelTx { transaction_id: 1 },
+        ExecuteMsg::SetPaloma {
+            chain_id: CHAIN_ID.to_string(),
+        },
+        ExecuteMsg::UpdateConfig {
+            retry_delay: Some(0),
+            proposal_ttl: None,
+        },
+        ExecuteMsg::ProposeOwner {
+            owners: vec![user.to_string()],
+        },
+        ExecuteMsg::AddLpToken {
+            lp_token: suite.lp_token.to_string(),
+            user: user.to_string(),
+            amount: Uint128::one(),
+        },
+    ];
+    for msg in msgs {
+        let err = suite.execute(&user, msg).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::Unauthorized {})
+        ));
+    }
+    assert!(suite.take_paloma_msgs().is_empty());
+}
+
+#[test]
+fn remove_unknown_owner() {
+    let mut suite = Suite::new();
+    let stranger: Addr = suite.app.api().addr_make("stranger");
+    let err = suite
+        .execute_as_owner(ExecuteMsg::RemoveOwner {
+            owner: stranger.to_string(),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::OwnerNotFound {})
+    ));
+}
+
+#[test]
+fn invalid_evm_address_is_rejected() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let err = suite
+        .execute_as_owner(ExecuteMsg::UpdateRefundWallet {
+            chain_id: CHAIN_ID.to_string(),
+            new_refund_wallet: "not-an-address".to_string(),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidEvmAddress { address }) if address == "not-an-address"
+    ));
+}
+
+#[test]
+fn instantiate_rejects_invalid_owner() {
+    let mut suite = Suite::new();
+    let code_id = suite.app.contract_data(&suite.trader).unwrap().code_id;
+    suite
+        .app
+        .instantiate_contract(
+            code_id,
+            suite.owner.clone(),
+            &InstantiateMsg {
+                retry_delay: 0,
+                owners: vec!["invalid".to_string()],
+                incentivizer: suite.incentivizer.clone(),
+                padex: PADEX.to_string(),
+                vepades: VEPADEX.to_string(),
+                approval_threshold: 1,
+                proposal_ttl: PROPOSAL_TTL,
+            },
+            &[],
+            "trader",
+            None,
+        )
+        .unwrap_err();
+    let err = suite
+        .app
+        .instantiate_contract(
+            code_id,
+            suite.owner.clone(),
+            &InstantiateMsg {
+                retry_delay: 0,
+                owners: vec![],
+                incentivizer: suite.incentivizer.clone(),
+                padex: PADEX.to_string(),
+                vepades: VEPADEX.to_string(),
+                approval_threshold: 1,
+                proposal_ttl: PROPOSAL_TTL,
+            },
+            &[],
+            "trader",
+            None,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::MinimumOwners {})
+    ));
+}
+
+#[test]
+fn operator_role_is_limited_to_trading() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let relayer = suite.app.api().addr_make("relayer");
+    suite
+        .execute_as_owner(ExecuteMsg::GrantRole {
+            role: Role::Operator,
+            address: relayer.to_string(),
+        })
+        .unwrap();
+
+    suite
+        .execute(&relayer, ExecuteMsg::CancelTx { transaction_id: 1 })
+        .unwrap();
+    for msg in [
+        ExecuteMsg::ProposeOwner {
+            owners: vec![relayer.to_string()],
+        },
+        ExecuteMsg::UpdateServiceFeeCollector {
+            chain_id: CHAIN_ID.to_string(),
+            new_service_fee_collector: EVM_ADDRESS.to_string(),
+        },
+        ExecuteMsg::GrantRole {
+            role: Role::FeeManager,
+            address: relayer.to_string(),
+        },
+        ExecuteMsg::SendToken {
+            chain_id: CHAIN_ID.to_string(),
+            tokens: vec![EVM_ADDRESS.to_string()],
+            to: EVM_RECEIVER.to_string(),
+            amounts: vec![Uint128::new(5)],
+            nonce: Uint128::one(),
+        },
+        ExecuteMsg::AddLpToken {
+            lp_token: suite.lp_token.to_string(),
+            user: relayer.to_string(),
+            amount: Uint128::one(),
+        },
+    ] {
+        let err = suite.execute(&relayer, msg).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::Unauthorized {})
+        ));
+    }
+
+    suite
+        .execute_as_owner(ExecuteMsg::RevokeRole {
+            role: Role::Operator,
+            address: relayer.to_string(),
+        })
+        .unwrap();
+    let err = suite
+        .execute(&relayer, ExecuteMsg::CancelTx { transaction_id: 1 })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+}
+
+#[test]
+fn fee_manager_role_updates_fees() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let fee_manager = suite.app.api().addr_make("fee_manager");
+    suite
+        .execute_as_owner(ExecuteMsg::GrantRole {
+            role: Role::FeeManager,
+            address: fee_manager.to_string(),
+        })
+        .unwrap();
+
+    suite
+        .execute(
+            &fee_manager,
+            ExecuteMsg::UpdateServiceFee {
+                chain_id: CHAIN_ID.to_string(),
+                new_service_fee: Uint256::one(),
+            },
+        )
+        .unwrap();
+    let err = suite
+        .execute(&fee_manager, ExecuteMsg::CancelTx { transaction_id: 1 })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+}
+
+#[test]
+fn roles_query_lists_grants() {
+    let mut suite = Suite::new();
+    let relayer = suite.app.api().addr_make("relayer");
+    for role in [Role::Operator, Role::Pauser, Role::Operator] {
+        suite
+            .execute_as_owner(ExecuteMsg::GrantRole {
+                role,
+                address: relayer.to_string(),
+            })
+            .unwrap();
+    }
+
+    let roles: RolesResponse = suite.query(&QueryMsg::Roles {});
+    assert_eq!(
+        roles,
+        RolesResponse {
+            owners: vec![suite.owner.clone()],
+            members: vec![RoleInfo {
+                address: relayer.clone(),
+                roles: vec![Role::Operator, Role::Pauser],
+            }],
+        }
+    );
+
+    let err = suite
+        .execute_as_owner(ExecuteMsg::RevokeRole {
+            role: Role::FeeManager,
+            address: relayer.to_string(),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::RoleNotFound {})
+    ));
+}
@@ -0,0 +1,793 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use cw_multi_test::BankSudo;
+
+use super::*;
+use crate::msg::{Asset, CallbackMsg, SimulateExchangeResponse, SwapOperation};
+use crate::state::PriceGuard;
+
+fn atom_to_usdc() -> Vec<SwapOperation> {
+    vec![SwapOperation::AstroSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: ATOM.to_string(),
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: USDC.to_string(),
+        },
+    }]
+}
+
+fn exchange_msg(suite: &Suite, operations: Vec<SwapOperation>, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations,
+        minimum_receive: None,
+        max_spread: None,
+        funds: vec![coin(amount, ATOM)],
+        offer_amount: None,
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Bridge,
+    }
+}
+
+#[test]
+fn exchange_bridges_swap_output() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(50, USDC)]);
+
+    let msg = exchange_msg(&suite, atom_to_usdc(), 1_000);
+    suite.execute_as_owner(msg).unwrap();
+
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![send_tx(coin(1_000 * ROUTER_RATE as u128, USDC))]
+    );
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::zero());
+}
+
+#[test]
+fn exchange_token_factory_denom_through_router() {
+    let mut suite = Suite::new();
+    let factory_denom = format!("factory/{}/ufoo", suite.faucet);
+    suite
+        .app
+        .sudo(
+            BankSudo::Mint {
+                to_address: suite.trader.to_string(),
+                amount: vec![coin(1_000, &factory_denom)],
+            }
+            .into(),
+        )
+        .unwrap();
+    suite.credit(EVM_RECEIVER, &factory_denom, 1_000);
+
+    // Token factory denoms are plain bank denoms, routed as `NativeToken` infos.
+    let operations = vec![SwapOperation::AstroSwap {
+        offer_asset_info: native(&factory_denom),
+        ask_asset_info: native(USDC),
+    }];
+    let mut msg = exchange_msg(&suite, operations, 1_000);
+    if let ExecuteMsg::Exchange { funds, .. } = &mut msg {
+        *funds = vec![coin(1_000, &factory_denom)];
+    }
+    suite.execute_as_owner(msg).unwrap();
+
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![send_tx(coin(1_000 * ROUTER_RATE as u128, USDC))]
+    );
+    assert!(suite.ledger(EVM_RECEIVER, &factory_denom).is_zero());
+    assert!(suite.balance(&suite.trader, &factory_denom).is_zero());
+}
+
+#[test]
+fn exchange_delivers_on_paloma() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(2_000, ATOM)]);
+    let user = suite.user.clone();
+
+    let mut msg = exchange_msg(&suite, atom_to_usdc(), 1_000);
+    if let ExecuteMsg::Exchange { delivery, .. } = &mut msg {
+        *delivery = Delivery::Send {
+            address: user.to_string(),
+        };
+    }
+    suite.execute_as_owner(msg).unwrap();
+    let output = Uint128::new(1_000 * ROUTER_RATE as u128);
+    assert_eq!(suite.balance(&user, USDC), output);
+
+    let mut msg = exchange_msg(&suite, atom_to_usdc(), 1_000);
+    if let ExecuteMsg::Exchange { delivery, .. } = &mut msg {
+        *delivery = Delivery::Credit;
+    }
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(suite.ledger(EVM_RECEIVER, USDC), output);
+    assert_eq!(suite.balance(&suite.trader, USDC), output);
+    assert!(suite.take_send_txs().is_empty());
+}
+
+#[test]
+fn exchange_credits_cw20_output() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    suite.mint_lp_to_trader(500);
+
+    let operations = vec![SwapOperation::AstroSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: ATOM.to_string(),
+        },
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: suite.lp_token.clone(),
+        },
+    }];
+    let msg = exchange_msg(&suite, operations, 1_000);
+    suite.execute_as_owner(msg).unwrap();
+
+    // Only the swap output is credited, not what the trader already held.
+    let lp_token = suite.lp_token.to_string();
+    let output = 1_000 * ROUTER_RATE as u128;
+    assert!(suite.take_send_txs().is_empty());
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(output));
+    assert_eq!(
+        suite.lp_token_balance(&suite.trader),
+        Uint128::new(500 + output)
+    );
+}
+
+#[test]
+fn exchange_fails_when_router_fails() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+
+    let msg = ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations: atom_to_usdc(),
+        minimum_receive: Some(Uint128::new(1_000_000)),
+        max_spread: None,
+        funds: vec![coin(1_000, ATOM)],
+        offer_amount: None,
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Bridge,
+    };
+    suite.execute_as_owner(msg).unwrap_err();
+    assert!(suite.take_paloma_msgs().is_empty());
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::new(1_000));
+}
+
+#[test]
+fn exchange_above_ledger_balance() {
+    let mut suite = Suite::new();
+    // Held by the contract, but owed to no one.
+    suite.fund_trader(&[coin(1_000, ATOM)]);
+    suite.credit(EVM_RECEIVER, ATOM, 400);
+
+    let msg = exchange_msg(&suite, atom_to_usdc(), 500);
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+    assert_eq!(suite.ledger(EVM_RECEIVER, ATOM), Uint128::new(400));
+}
+
+#[test]
+fn send_to_evm_bridges_amounts() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(100, ATOM), coin(300, PADEX)]);
+
+    suite
+        .execute_as_owner(ExecuteMsg::SendToEVM {
+            chain_id: CHAIN_ID.to_string(),
+            amounts: vec!["100uatom".to_string(), "200upadex".to_string()],
+            recipient: EVM_RECEIVER.to_string(),
+        })
+        .unwrap();
+
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![send_tx(coin(100, ATOM)), send_tx(coin(200, PADEX))]
+    );
+    assert!(suite.ledger(EVM_RECEIVER, ATOM).is_zero());
+    assert_eq!(suite.ledger(EVM_RECEIVER, PADEX), Uint128::new(100));
+}
+
+#[test]
+fn send_to_evm_debits_padex_balance() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(300, PADEX)]);
+    let send = |amount: &str| ExecuteMsg::SendToEVM {
+        chain_id: CHAIN_ID.to_string(),
+        amounts: vec![amount.to_string()],
+        recipient: EVM_RECEIVER.to_string(),
+    };
+
+    let err = suite.execute_as_owner(send("301upadex")).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+    suite.execute_as_owner(send("300upadex")).unwrap();
+    assert_eq!(suite.take_send_txs(), vec![send_tx(coin(300, PADEX))]);
+    assert!(suite.ledger(EVM_RECEIVER, PADEX).is_zero());
+}
+
+#[test]
+fn send_to_evm_rejects_malformed_amount() {
+    let mut suite = Suite::new();
+    suite
+        .execute_as_owner(ExecuteMsg::SendToEVM {
+            chain_id: CHAIN_ID.to_string(),
+            amounts: vec!["uatom".to_string()],
+            recipient: EVM_RECEIVER.to_string(),
+        })
+        .unwrap_err();
+    assert!(suite.take_paloma_msgs().is_empty());
+}
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+#[test]
+fn exchange_validates_route() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+
+    let msg = exchange_msg(&suite, vec![], 1_000);
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::EmptyRoute {})
+    ));
+
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native(ATOM),
+            ask_asset_info: native(USDC),
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: native(PADEX),
+            ask_asset_info: native(ATOM),
+        },
+    ];
+    let msg = exchange_msg(&suite, operations, 1_000);
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidRoute { hop: 1 })
+    ));
+    assert!(suite.take_paloma_msgs().is_empty());
+}
+
+#[test]
+fn exchange_through_pair_hop() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+
+    let operations = vec![SwapOperation::PairSwap {
+        pair: suite.pair.clone(),
+        offer_asset_info: native(ATOM),
+        ask_asset_info: native(USDC),
+    }];
+    let msg = exchange_msg(&suite, operations, 1_000);
+    suite.execute_as_owner(msg).unwrap();
+
+    // 1_000_000 * 1_000 / 1_001_000 = 999, minus the 30 bps pool fee.
+    assert_eq!(suite.take_send_txs(), vec![send_tx(coin(996, USDC))]);
+}
+
+#[test]
+fn exchange_mixed_circular_route() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native(ATOM),
+            ask_asset_info: native(USDC),
+        },
+        SwapOperation::PairSwap {
+            pair: suite.pair.clone(),
+            offer_asset_info: native(USDC),
+            ask_asset_info: native(ATOM),
+        },
+    ];
+    let msg = exchange_msg(&suite, operations.clone(), 1_000);
+    suite.execute_as_owner(msg).unwrap();
+
+    // The router pays 2_000 USDC, which the pair turns into 1_990 ATOM.
+    assert_eq!(suite.take_send_txs(), vec![send_tx(coin(1_990, ATOM))]);
+
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    let msg = ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations,
+        minimum_receive: Some(Uint128::new(2_000)),
+        max_spread: None,
+        funds: vec![coin(1_000, ATOM)],
+        offer_amount: None,
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Bridge,
+    };
+    suite.execute_as_owner(msg).unwrap_err();
+    assert!(suite.take_paloma_msgs().is_empty());
+}
+
+#[test]
+fn route_callback_is_internal() {
+    let mut suite = Suite::new();
+    let msg = ExecuteMsg::Callback(CallbackMsg::ContinueRoute {
+        dex_router: suite.router.clone(),
+        operations: vec![],
+        prev_balance: Asset {
+            info: native(ATOM),
+            amount: Uint128::zero(),
+        },
+        hop_minimum: None,
+        minimum_receive: None,
+        max_spread: None,
+    });
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+}
+
+#[test]
+fn exchange_with_offer_amount() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+
+    let msg = ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations: atom_to_usdc(),
+        minimum_receive: None,
+        max_spread: None,
+        funds: vec![],
+        offer_amount: Some(Uint128::new(400)),
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Bridge,
+    };
+    suite.execute_as_owner(msg).unwrap();
+
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![send_tx(coin(400 * ROUTER_RATE as u128, USDC))]
+    );
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::new(600));
+}
+
+#[test]
+fn exchange_rejects_mismatched_funds() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+
+    let cases = vec![
+        (vec![], None),
+        (vec![coin(1_000, USDC)], None),
+        (vec![coin(500, ATOM), coin(500, USDC)], None),
+        (vec![coin(500, ATOM)], Some(Uint128::new(500))),
+        (vec![], Some(Uint128::zero())),
+    ];
+    for (funds, offer_amount) in cases {
+        let msg = ExecuteMsg::Exchange {
+            dex_router: suite.router.clone(),
+            operations: atom_to_usdc(),
+            minimum_receive: None,
+            max_spread: None,
+            funds,
+            offer_amount,
+            chain_id: CHAIN_ID.to_string(),
+            recipient: EVM_RECEIVER.to_string(),
+            refund_on_failure: false,
+            delivery: Delivery::Bridge,
+        };
+        let err = suite.execute_as_owner(msg).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::FundsMismatch {})
+        ));
+    }
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::new(1_000));
+}
+
+#[test]
+fn simulate_exchange_matches_execution() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native(ATOM),
+            ask_asset_info: native(USDC),
+        },
+        SwapOperation::PairSwap {
+            pair: suite.pair.clone(),
+            offer_asset_info: native(USDC),
+            ask_asset_info: native(ATOM),
+        },
+    ];
+
+    let simulation: SimulateExchangeResponse = suite.query(&QueryMsg::SimulateExchange {
+        dex_router: suite.router.clone(),
+        operations: operations.clone(),
+        offer_amount: Uint128::new(1_000),
+    });
+    assert_eq!(simulation.return_amount, Uint128::new(1_990));
+    // The router hop only reports its output; the pair hop reports fees and spread.
+    let [router_hop, pair_hop] = &simulation.swaps[..] else {
+        panic!("expected two swaps");
+    };
+    assert_eq!(router_hop.return_amount, Uint128::new(2_000));
+    assert_eq!(router_hop.commission_amount, None);
+    assert_eq!(pair_hop.offer_amount, Uint128::new(2_000));
+    assert_eq!(pair_hop.commission_amount, Some(Uint128::new(6)));
+    assert_eq!(pair_hop.spread_amount, Some(Uint128::new(4)));
+    assert_eq!(pair_hop.price_impact, Some(Decimal::permille(2)));
+
+    let msg = exchange_msg(&suite, operations, 1_000);
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![send_tx(coin(simulation.return_amount.u128(), ATOM))]
+    );
+
+    // Router-only routes are simulated as a single router call.
+    let simulation: SimulateExchangeResponse = suite.query(&QueryMsg::SimulateExchange {
+        dex_router: suite.router.clone(),
+        operations: atom_to_usdc(),
+        offer_amount: Uint128::new(1_000),
+    });
+    assert_eq!(simulation.swaps.len(), 1);
+    assert_eq!(simulation.return_amount, Uint128::new(2_000));
+
+    let err = suite
+        .app
+        .wrap()
+        .query_wasm_smart::<SimulateExchangeResponse>(
+            &suite.trader,
+            &QueryMsg::SimulateExchange {
+                dex_router: suite.router.clone(),
+                operations: vec![],
+                offer_amount: Uint128::new(1_000),
+            },
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Swap route is empty"));
+}
+
+#[test]
+fn price_guard_bounds_pair_hops() {
+    let mut suite = Suite::new();
+    let pair_hop = vec![SwapOperation::PairSwap {
+        pair: suite.pair.clone(),
+        offer_asset_info: native(ATOM),
+        ask_asset_info: native(USDC),
+    }];
+    let set_guard = |suite: &mut Suite, guard: PriceGuard| {
+        suite
+            .execute_as_owner(ExecuteMsg::SetPriceGuard {
+                pair: suite.pair.clone(),
+                guard: Some(guard),
+            })
+            .unwrap();
+    };
+
+    // The TWAP is 1 USDC per ATOM and the hop pays 996, within 1%.
+    set_guard(
+        &mut suite,
+        PriceGuard {
+            max_deviation: Decimal::percent(1),
+            reference_prices: vec![],
+        },
+    );
+    suite
+        .app
+        .update_block(|block| block.time = block.time.plus_seconds(60));
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    let msg = exchange_msg(&suite, pair_hop.clone(), 1_000);
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(suite.take_send_txs(), vec![send_tx(coin(996, USDC))]);
+
+    // A reference price of 1.1 puts the floor at 1_089.
+    let guard = PriceGuard {
+        max_deviation: Decimal::percent(1),
+        reference_prices: vec![(native(ATOM), native(USDC), Decimal::percent(110))],
+    };
+    set_guard(&mut suite, guard.clone());
+    let stored: Option<PriceGuard> = suite.query(&QueryMsg::PriceGuard {
+        pair: suite.pair.clone(),
+    });
+    assert_eq!(stored, Some(guard));
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    let msg = exchange_msg(&suite, pair_hop.clone(), 1_000);
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::PriceDeviation {})
+    ));
+
+    // An explicit minimum_receive replaces the guard.
+    let msg = ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations: pair_hop,
+        minimum_receive: Some(Uint128::one()),
+        max_spread: None,
+        funds: vec![coin(1_000, ATOM)],
+        offer_amount: None,
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Bridge,
+    };
+    suite.execute_as_owner(msg).unwrap();
+}
+
+#[test]
+fn price_guard_bounds_router_hops() {
+    let mut suite = Suite::new();
+    suite
+        .execute_as_owner(ExecuteMsg::SetPriceGuard {
+            pair: suite.pair.clone(),
+            guard: Some(PriceGuard {
+                max_deviation: Decimal::percent(1),
+                reference_prices: vec![],
+            }),
+        })
+        .unwrap();
+    suite
+        .app
+        .update_block(|block| block.time = block.time.plus_seconds(60));
+
+    // The router pays 2 USDC per ATOM, above the floor set by the TWAP of 1.
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    let msg = exchange_msg(&suite, atom_to_usdc(), 1_000);
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(suite.take_send_txs(), vec![send_tx(coin(2_000, USDC))]);
+
+    // A router paying half the TWAP trips the guard of the pair trading the same assets.
+    let code_id = suite.app.contract_data(&suite.router).unwrap().code_id;
+    let router = suite
+        .app
+        .instantiate_contract(
+            code_id,
+            suite.owner.clone(),
+            &mocks::router::InstantiateMsg {
+                rate: Decimal::percent(50),
+                reported_rate: None,
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+    suite
+        .app
+        .send_tokens(
+            suite.faucet.clone(),
+            router.clone(),
+            &[coin(10_000, ATOM), coin(10_000, USDC)],
+        )
+        .unwrap();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, PADEX)]);
+    let mut msg = exchange_msg(&suite, atom_to_usdc(), 1_000);
+    if let ExecuteMsg::Exchange { dex_router, .. } = &mut msg {
+        *dex_router = router.clone();
+    }
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::PriceDeviation {})
+    ));
+
+    // Behind an unguarded hop the route is walked, so the guarded hop is still floored.
+    let msg = ExecuteMsg::Exchange {
+        dex_router: router,
+        operations: vec![
+            SwapOperation::AstroSwap {
+                offer_asset_info: native(PADEX),
+                ask_asset_info: native(ATOM),
+            },
+            SwapOperation::AstroSwap {
+                offer_asset_info: native(ATOM),
+                ask_asset_info: native(USDC),
+            },
+        ],
+        minimum_receive: None,
+        max_spread: None,
+        funds: vec![coin(1_000, PADEX)],
+        offer_amount: None,
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Bridge,
+    };
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::PriceDeviation {})
+    ));
+    assert!(suite.take_paloma_msgs().is_empty());
+}
+
+#[test]
+fn unguarding_one_pair_keeps_router_hops_floored() {
+    let mut suite = Suite::new();
+    let (second_pair, _) = suite.add_pair(vec![native(ATOM), native(USDC)], PairType::Xyk {});
+    suite
+        .app
+        .execute_contract(
+            suite.faucet.clone(),
+            second_pair.clone(),
+            &mocks::pair::ExecuteMsg::ProvideLiquidity {
+                assets: vec![
+                    Asset {
+                        info: native(ATOM),
+                        amount: Uint128::new(POOL_RESERVE),
+                    },
+                    Asset {
+                        info: native(USDC),
+                        amount: Uint128::new(POOL_RESERVE),
+                    },
+                ],
+                slippage_tolerance: None,
+                receiver: None,
+            },
+            &[coin(POOL_RESERVE, ATOM), coin(POOL_RESERVE, USDC)],
+        )
+        .unwrap();
+    let guard = PriceGuard {
+        max_deviation: Decimal::percent(1),
+        reference_prices: vec![],
+    };
+    for pair in [suite.pair.clone(), second_pair.clone()] {
+        suite
+            .execute_as_owner(ExecuteMsg::SetPriceGuard {
+                pair,
+                guard: Some(guard.clone()),
+            })
+            .unwrap();
+    }
+    // Dropping the second guard leaves the first pair guarding ATOM/USDC router hops.
+    suite
+        .execute_as_owner(ExecuteMsg::SetPriceGuard {
+            pair: second_pair,
+            guard: None,
+        })
+        .unwrap();
+    suite
+        .app
+        .update_block(|block| block.time = block.time.plus_seconds(60));
+
+    let code_id = suite.app.contract_data(&suite.router).unwrap().code_id;
+    let router = suite
+        .app
+        .instantiate_contract(
+            code_id,
+            suite.owner.clone(),
+            &mocks::router::InstantiateMsg {
+                rate: Decimal::percent(50),
+                reported_rate: None,
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+    suite
+        .app
+        .send_tokens(suite.faucet.clone(), router.clone(), &[coin(10_000, USDC)])
+        .unwrap();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    let mut msg = exchange_msg(&suite, atom_to_usdc(), 1_000);
+    if let ExecuteMsg::Exchange { dex_router, .. } = &mut msg {
+        *dex_router = router;
+    }
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::PriceDeviation {})
+    ));
+}
+
+#[test]
+fn exchange_rejects_misreported_output() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    let code_id = suite.app.contract_data(&suite.router).unwrap().code_id;
+    let router = suite
+        .app
+        .instantiate_contract(
+            code_id,
+            suite.owner.clone(),
+            &mocks::router::InstantiateMsg {
+                rate: Decimal::from_atomics(ROUTER_RATE, 0).unwrap(),
+                reported_rate: Some(Decimal::one()),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+    suite
+        .app
+        .send_tokens(suite.faucet.clone(), router.clone(), &[coin(10_000, USDC)])
+        .unwrap();
+
+    let msg = ExecuteMsg::Exchange {
+        dex_router: router,
+        operations: atom_to_usdc(),
+        minimum_receive: None,
+        max_spread: None,
+        funds: vec![coin(1_000, ATOM)],
+        offer_amount: None,
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Bridge,
+    };
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::OutputMismatch { reported, measured })
+            if reported.u128() == 1_000 && measured.u128() == 2_000
+    ));
+    assert!(suite.take_paloma_msgs().is_empty());
+}
+
+#[test]
+fn failed_exchange_refunds_into_ledger() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+
+    let msg = ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations: atom_to_usdc(),
+        minimum_receive: Some(Uint128::new(1_000_000)),
+        max_spread: None,
+        funds: vec![coin(1_000, ATOM)],
+        offer_amount: None,
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: true,
+        delivery: Delivery::Bridge,
+    };
+    let res = suite.execute_as_owner(msg).unwrap();
+
+    let event = res
+        .events
+        .iter()
+        .find(|event| event.ty == "wasm-operation_failed")
+        .unwrap();
+    assert!(event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "error" && attr.value.contains("minimum receive")));
+    assert!(suite.take_paloma_msgs().is_empty());
+    assert_eq!(suite.ledger(EVM_RECEIVER, ATOM), Uint128::new(1_000));
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::new(1_000));
+
+    // The refund is withdrawn once.
+    let withdraw = ExecuteMsg::SendToEVM {
+        chain_id: CHAIN_ID.to_string(),
+        amounts: vec!["1000uatom".to_string()],
+        recipient: EVM_RECEIVER.to_string(),
+    };
+    suite.execute_as_owner(withdraw.clone()).unwrap();
+    assert_eq!(suite.take_send_txs(), vec![send_tx(coin(1_000, ATOM))]);
+    assert!(suite.ledger(EVM_RECEIVER, ATOM).is_zero());
+    let err = suite.execute_as_owner(withdraw).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+}
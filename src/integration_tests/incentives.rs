@@ -0,0 +1,204 @@
+use cosmwasm_std::{coin, Uint128};
+
+use super::*;
+
+fn staked(suite: &Suite, user: &Addr) -> Uint128 {
+    suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            suite.incentivizer.clone(),
+            &mocks::incentivizer::QueryMsg::Deposit {
+                lp_token: suite.lp_token.to_string(),
+                user: user.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+fn lock(suite: &Suite, user: &Addr) -> (Uint128, u64) {
+    suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            suite.vepadex.clone(),
+            &mocks::vepadex::QueryMsg::Lock {
+                user: user.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+/// Gives `user` 1000 LP tokens held by the trader.
+fn setup_lp(suite: &mut Suite) -> String {
+    let lp_token = suite.lp_token.to_string();
+    let user = suite.user.to_string();
+    suite.mint_lp_to_trader(1_000);
+    suite.credit(&user, &lp_token, 1_000);
+    lp_token
+}
+
+fn deposit_msg(suite: &Suite, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Deposit {
+        incentivizer: suite.incentivizer.clone(),
+        token: suite.lp_token.to_string(),
+        amount: Uint128::new(amount),
+        depositor: suite.user.to_string(),
+    }
+}
+
+#[test]
+fn deposit_stakes_lp_tokens() {
+    let mut suite = Suite::new();
+    let lp_token = setup_lp(&mut suite);
+
+    let msg = deposit_msg(&suite, 600);
+    suite.execute_as_owner(msg).unwrap();
+
+    let user = suite.user.clone();
+    assert_eq!(staked(&suite, &user), Uint128::new(600));
+    assert_eq!(suite.ledger(user.as_str(), &lp_token), Uint128::new(400));
+    assert_eq!(suite.lp_token_balance(&suite.trader), Uint128::new(400));
+}
+
+#[test]
+fn deposit_above_ledger_balance() {
+    let mut suite = Suite::new();
+    setup_lp(&mut suite);
+    let msg = deposit_msg(&suite, 1_001);
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+}
+
+#[test]
+fn withdraw_unstakes_lp_tokens() {
+    let mut suite = Suite::new();
+    let lp_token = setup_lp(&mut suite);
+    let msg = deposit_msg(&suite, 1_000);
+    suite.execute_as_owner(msg).unwrap();
+
+    let user = suite.user.clone();
+    suite
+        .execute_as_owner(ExecuteMsg::Withdraw {
+            incentivizer: suite.incentivizer.clone(),
+            token: lp_token.clone(),
+            amount: Uint128::new(1_000),
+            recipient: user.to_string(),
+        })
+        .unwrap();
+
+    assert_eq!(staked(&suite, &user), Uint128::zero());
+    assert_eq!(suite.ledger(user.as_str(), &lp_token), Uint128::new(1_000));
+    assert_eq!(suite.lp_token_balance(&suite.trader), Uint128::new(1_000));
+    assert_eq!(
+        suite.balance(&suite.trader, PADEX),
+        Uint128::new(PADEX_REWARD)
+    );
+    assert_eq!(
+        suite.ledger(user.as_str(), PADEX),
+        Uint128::new(PADEX_REWARD)
+    );
+}
+
+#[test]
+fn claim_rewards_collects_padex() {
+    let mut suite = Suite::new();
+    // Pre-existing PADEX in the contract must not be attributed to the user.
+    suite.fund_trader(&[coin(1_000, PADEX)]);
+    let res = suite
+        .execute_as_owner(ExecuteMsg::ClaimRewards {
+            incentivizer: suite.incentivizer.clone(),
+            tokens: vec![suite.lp_token.to_string()],
+            recipient: EVM_RECEIVER.to_string(),
+        })
+        .unwrap();
+
+    assert_eq!(
+        suite.balance(&suite.trader, PADEX),
+        Uint128::new(1_000 + PADEX_REWARD)
+    );
+    assert_eq!(
+        suite.ledger(EVM_RECEIVER, PADEX),
+        Uint128::new(PADEX_REWARD)
+    );
+    assert!(res.has_event(
+        &cosmwasm_std::Event::new("wasm-padex_diff")
+            .add_attribute("user", EVM_RECEIVER)
+            .add_attribute("credited", PADEX_REWARD.to_string())
+    ));
+}
+
+#[test]
+fn vepadex_lock_lifecycle() {
+    let mut suite = Suite::new();
+    let user = suite.user.clone();
+    suite.fund_trader(&[coin(500, PADEX)]);
+    suite.credit(user.as_str(), PADEX, 500);
+
+    suite
+        .execute_as_owner(ExecuteMsg::CreateLock {
+            vepadex: suite.vepadex.clone(),
+            coin: coin(500, PADEX),
+            end_lock_time: 10,
+            user: user.to_string(),
+        })
+        .unwrap();
+    assert_eq!(lock(&suite, &user), (Uint128::new(500), 10));
+    assert_eq!(suite.ledger(user.as_str(), PADEX), Uint128::zero());
+
+    suite.fund_trader(&[coin(200, PADEX)]);
+    suite.credit(user.as_str(), PADEX, 200);
+
+    suite
+        .execute_as_owner(ExecuteMsg::IncreaseLockAmount {
+            vepadex: suite.vepadex.clone(),
+            user: user.to_string(),
+            coin: coin(200, PADEX),
+        })
+        .unwrap();
+    assert_eq!(lock(&suite, &user), (Uint128::new(700), 10));
+
+    suite
+        .execute_as_owner(ExecuteMsg::IncreaseEndLockTime {
+            vepadex: suite.vepadex.clone(),
+            end_lock_time: 20,
+            user: user.to_string(),
+        })
+        .unwrap();
+    assert_eq!(lock(&suite, &user), (Uint128::new(700), 20));
+
+    suite
+        .execute_as_owner(ExecuteMsg::Unlock {
+            vepadex: suite.vepadex.clone(),
+            user: user.to_string(),
+        })
+        .unwrap();
+    assert_eq!(lock(&suite, &user), (Uint128::zero(), 0));
+    assert_eq!(suite.balance(&suite.trader, PADEX), Uint128::new(700));
+    assert_eq!(suite.ledger(user.as_str(), PADEX), Uint128::new(700));
+}
+
+#[test]
+fn create_lock_above_ledger_balance() {
+    let mut suite = Suite::new();
+    let user = suite.user.clone();
+    suite.fund_trader(&[coin(500, PADEX)]);
+    suite.credit(user.as_str(), PADEX, 100);
+
+    let err = suite
+        .execute_as_owner(ExecuteMsg::CreateLock {
+            vepadex: suite.vepadex.clone(),
+            coin: coin(500, PADEX),
+            end_lock_time: 10,
+            user: user.to_string(),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+    assert_eq!(suite.ledger(user.as_str(), PADEX), Uint128::new(100));
+}
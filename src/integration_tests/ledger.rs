@@ -0,0 +1,422 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use cw_multi_test::{BankSudo, Executor};
+
+use super::*;
+use crate::msg::{LpBalanceInfo, LpTotalInfo, ReconcileResponse, SwapOperation};
+
+const ALICE: &str = "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const BOB: &str = "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn info(user: &str, lp_token: &str, amount: u128) -> LpBalanceInfo {
+    LpBalanceInfo {
+        user: user.to_string(),
+        lp_token: lp_token.to_string(),
+        amount: Uint128::new(amount),
+    }
+}
+
+/// Credits ALICE with "lp_a" and "lp_b" and BOB with "lp_a", backed by minted bank coins.
+fn seed(suite: &mut Suite) {
+    suite
+        .app
+        .sudo(
+            BankSudo::Mint {
+                to_address: suite.trader.to_string(),
+                amount: vec![coin(40, "lp_a"), coin(20, "lp_b")],
+            }
+            .into(),
+        )
+        .unwrap();
+    suite.credit(ALICE, "lp_a", 10);
+    suite.credit(ALICE, "lp_b", 20);
+    suite.credit(BOB, "lp_a", 30);
+}
+
+#[test]
+fn lp_balances_pages_by_token() {
+    let mut suite = Suite::new();
+    seed(&mut suite);
+
+    let page: Vec<LpBalanceInfo> = suite.query(&QueryMsg::LpBalances {
+        user: ALICE.to_string(),
+        start_after: None,
+        limit: Some(1),
+    });
+    assert_eq!(page, vec![info(ALICE, "lp_a", 10)]);
+    let page: Vec<LpBalanceInfo> = suite.query(&QueryMsg::LpBalances {
+        user: ALICE.to_string(),
+        start_after: Some("lp_a".to_string()),
+        limit: None,
+    });
+    assert_eq!(page, vec![info(ALICE, "lp_b", 20)]);
+}
+
+#[test]
+fn lp_holders_pages_by_user() {
+    let mut suite = Suite::new();
+    seed(&mut suite);
+
+    let page: Vec<LpBalanceInfo> = suite.query(&QueryMsg::LpHolders {
+        lp_token: "lp_a".to_string(),
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(page, vec![info(ALICE, "lp_a", 10), info(BOB, "lp_a", 30)]);
+    let page: Vec<LpBalanceInfo> = suite.query(&QueryMsg::LpHolders {
+        lp_token: "lp_a".to_string(),
+        start_after: Some(ALICE.to_string()),
+        limit: None,
+    });
+    assert_eq!(page, vec![info(BOB, "lp_a", 30)]);
+
+    // The index follows debits made by the contract.
+    suite.set_chain_setting();
+    suite.deposit(ALICE, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+    suite
+        .execute_as_owner(ExecuteMsg::AddLiquidity {
+            pair: suite.pair.clone(),
+            coins: vec![coin(1_000, ATOM), coin(1_000, USDC)],
+            slippage_tolerance: None,
+            depositor: ALICE.to_string(),
+            refund_on_failure: false,
+            min_lp_out: None,
+        })
+        .unwrap();
+    let lp_token = suite.lp_token.to_string();
+    suite
+        .execute_as_owner(ExecuteMsg::RemoveLiquidity {
+            chain_id: CHAIN_ID.to_string(),
+            pair: suite.pair.clone(),
+            amount: Uint128::new(500),
+            receiver: ALICE.to_string(),
+            min_assets_out: vec![],
+            receive_asset: None,
+            delivery: Delivery::Bridge,
+        })
+        .unwrap();
+    let page: Vec<LpBalanceInfo> = suite.query(&QueryMsg::LpHolders {
+        lp_token: lp_token.clone(),
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(page, vec![info(ALICE, &lp_token, 1_500)]);
+}
+
+#[test]
+fn lp_totals_sums_holders() {
+    let mut suite = Suite::new();
+    seed(&mut suite);
+
+    let totals: Vec<LpTotalInfo> = suite.query(&QueryMsg::LpTotals {
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(
+        totals,
+        vec![
+            LpTotalInfo {
+                lp_token: "lp_a".to_string(),
+                total: Uint128::new(40),
+            },
+            LpTotalInfo {
+                lp_token: "lp_b".to_string(),
+                total: Uint128::new(20),
+            },
+        ]
+    );
+    let totals: Vec<LpTotalInfo> = suite.query(&QueryMsg::LpTotals {
+        start_after: Some("lp_a".to_string()),
+        limit: Some(1),
+    });
+    assert_eq!(totals.len(), 1);
+    assert_eq!(totals[0].lp_token, "lp_b");
+}
+
+#[test]
+fn migrate_keeps_holder_index() {
+    let mut suite = Suite::new();
+    seed(&mut suite);
+    let code_id = suite.app.contract_data(&suite.trader).unwrap().code_id;
+    suite
+        .app
+        .migrate_contract(
+            suite.owner.clone(),
+            suite.trader.clone(),
+            &MigrateMsg {
+                incentivizer: suite.incentivizer.clone(),
+                padex: PADEX.to_string(),
+                vepades: VEPADEX.to_string(),
+                approval_threshold: 1,
+                proposal_ttl: PROPOSAL_TTL,
+            },
+            code_id,
+        )
+        .unwrap();
+
+    let page: Vec<LpBalanceInfo> = suite.query(&QueryMsg::LpHolders {
+        lp_token: "lp_a".to_string(),
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(page, vec![info(ALICE, "lp_a", 10), info(BOB, "lp_a", 30)]);
+}
+
+#[test]
+fn reconcile_reports_drift() {
+    let mut suite = Suite::new();
+    let user = suite.user.to_string();
+    suite.deposit(&user, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+    suite
+        .execute_as_owner(ExecuteMsg::AddLiquidity {
+            pair: suite.pair.clone(),
+            coins: vec![coin(1_000, ATOM), coin(1_000, USDC)],
+            slippage_tolerance: None,
+            depositor: user.clone(),
+            refund_on_failure: false,
+            min_lp_out: None,
+        })
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::Deposit {
+            incentivizer: suite.incentivizer.clone(),
+            token: suite.lp_token.to_string(),
+            amount: Uint128::new(600),
+            depositor: user.clone(),
+        })
+        .unwrap();
+
+    let lp_token = suite.lp_token.to_string();
+    let report: ReconcileResponse = suite.query(&QueryMsg::Reconcile {
+        lp_token: lp_token.clone(),
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(
+        report,
+        ReconcileResponse {
+            internal_total: Uint128::new(1_400),
+            contract_balance: Uint128::new(1_400),
+            staked_balance: Uint128::new(600),
+            last_holder: Some(user.clone()),
+            surplus: Uint128::zero(),
+            deficit: Uint128::zero(),
+        }
+    );
+    // The next page has no holders left, so nothing staked is summed.
+    let report: ReconcileResponse = suite.query(&QueryMsg::Reconcile {
+        lp_token: lp_token.clone(),
+        start_after: Some(user.clone()),
+        limit: Some(1),
+    });
+    assert!(report.staked_balance.is_zero());
+    assert_eq!(report.last_holder, None);
+    assert_eq!(report.internal_total, Uint128::new(1_400));
+
+    // Tokens leaving without a debit show up as a deficit.
+    suite
+        .app
+        .execute_contract(
+            suite.trader.clone(),
+            suite.lp_token.clone(),
+            &cw20::Cw20ExecuteMsg::Transfer {
+                recipient: suite.user.to_string(),
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap();
+    let report: ReconcileResponse = suite.query(&QueryMsg::Reconcile {
+        lp_token,
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(report.deficit, Uint128::new(100));
+    assert!(report.surplus.is_zero());
+
+    // Native tokens are compared with the bank balance.
+    suite.fund_trader(&[coin(50, PADEX)]);
+    let report: ReconcileResponse = suite.query(&QueryMsg::Reconcile {
+        lp_token: PADEX.to_string(),
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(report.surplus, Uint128::new(50));
+}
+
+#[test]
+fn ledger_liabilities_block_bridging() {
+    let mut suite = Suite::new();
+    suite.deposit(ALICE, &[coin(200, ATOM)]);
+    suite.deposit(BOB, &[coin(100, ATOM)]);
+    let totals: Vec<LpTotalInfo> = suite.query(&QueryMsg::LpTotals {
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(
+        totals,
+        vec![LpTotalInfo {
+            lp_token: ATOM.to_string(),
+            total: Uint128::new(300),
+        }]
+    );
+
+    // BOB may only withdraw his own 100, not ALICE's.
+    let send = |amount: &str| ExecuteMsg::SendToEVM {
+        chain_id: CHAIN_ID.to_string(),
+        amounts: vec![amount.to_string()],
+        recipient: BOB.to_string(),
+    };
+    let err = suite.execute_as_owner(send("150uatom")).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+    suite.execute_as_owner(send("100uatom")).unwrap();
+    assert_eq!(suite.ledger(ALICE, ATOM), Uint128::new(200));
+    assert!(suite.ledger(BOB, ATOM).is_zero());
+}
+
+#[test]
+fn exchange_funded_from_ledger() {
+    let mut suite = Suite::new();
+    suite.fund_trader(&[coin(1_000, ATOM)]);
+    suite.credit(ALICE, ATOM, 600);
+
+    let exchange = |amount: u128| ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations: vec![SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: ATOM.to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: USDC.to_string(),
+            },
+        }],
+        minimum_receive: None,
+        max_spread: None,
+        funds: vec![],
+        offer_amount: Some(Uint128::new(amount)),
+        chain_id: CHAIN_ID.to_string(),
+        recipient: ALICE.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Credit,
+    };
+    let (first, second) = (exchange(500), exchange(500));
+    suite.execute_as_owner(first).unwrap();
+    assert_eq!(suite.ledger(ALICE, ATOM), Uint128::new(100));
+    assert_eq!(
+        suite.ledger(ALICE, USDC),
+        Uint128::new(500 * ROUTER_RATE as u128)
+    );
+
+    let err = suite.execute_as_owner(second).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+    assert_eq!(suite.ledger(ALICE, ATOM), Uint128::new(100));
+}
+
+#[test]
+fn exchange_cannot_spend_another_users_balance() {
+    let mut suite = Suite::new();
+    suite.deposit(ALICE, &[coin(600, ATOM)]);
+
+    let msg = ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations: vec![SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: ATOM.to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: USDC.to_string(),
+            },
+        }],
+        minimum_receive: None,
+        max_spread: None,
+        funds: vec![],
+        offer_amount: Some(Uint128::new(500)),
+        chain_id: CHAIN_ID.to_string(),
+        recipient: BOB.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Credit,
+    };
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+    assert_eq!(suite.ledger(ALICE, ATOM), Uint128::new(600));
+    assert!(suite.ledger(BOB, USDC).is_zero());
+}
+
+#[test]
+fn add_liquidity_reuses_withdrawn_balance() {
+    let mut suite = Suite::new();
+    suite.deposit(ALICE, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+    let add_liquidity =
+        |suite: &Suite, coins: Vec<Coin>, depositor: &str| ExecuteMsg::AddLiquidity {
+            pair: suite.pair.clone(),
+            coins,
+            slippage_tolerance: Some(Decimal::percent(1)),
+            depositor: depositor.to_string(),
+            refund_on_failure: true,
+            min_lp_out: None,
+        };
+    let msg = add_liquidity(&suite, vec![coin(1_000, ATOM), coin(1_000, USDC)], ALICE);
+    suite.execute_as_owner(msg).unwrap();
+
+    // Withdraw into the ledger, then provide again from it.
+    suite
+        .execute_as_owner(ExecuteMsg::RemoveLiquidity {
+            chain_id: CHAIN_ID.to_string(),
+            pair: suite.pair.clone(),
+            amount: Uint128::new(1_000),
+            receiver: ALICE.to_string(),
+            min_assets_out: vec![],
+            receive_asset: None,
+            delivery: Delivery::Credit,
+        })
+        .unwrap();
+    assert_eq!(suite.ledger(ALICE, ATOM), Uint128::new(500));
+    assert_eq!(suite.ledger(ALICE, USDC), Uint128::new(500));
+
+    let msg = add_liquidity(&suite, vec![coin(500, ATOM), coin(500, USDC)], ALICE);
+    suite.execute_as_owner(msg).unwrap();
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(ALICE, &lp_token), Uint128::new(2_000));
+    assert!(suite.ledger(ALICE, ATOM).is_zero());
+    assert!(suite.ledger(ALICE, USDC).is_zero());
+
+    // A failed provision refunds the depositor.
+    suite.deposit(BOB, &[coin(200, ATOM), coin(100, USDC)]);
+    let msg = add_liquidity(&suite, vec![coin(200, ATOM), coin(100, USDC)], BOB);
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(suite.ledger(BOB, ATOM), Uint128::new(200));
+    assert_eq!(suite.ledger(BOB, USDC), Uint128::new(100));
+    assert!(suite.ledger(ALICE, ATOM).is_zero());
+}
+
+#[test]
+fn add_liquidity_cannot_spend_another_users_balance() {
+    let mut suite = Suite::new();
+    suite.deposit(ALICE, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+
+    let err = suite
+        .execute_as_owner(ExecuteMsg::AddLiquidity {
+            pair: suite.pair.clone(),
+            coins: vec![coin(1_000, ATOM), coin(1_000, USDC)],
+            slippage_tolerance: Some(Decimal::percent(1)),
+            depositor: BOB.to_string(),
+            refund_on_failure: false,
+            min_lp_out: None,
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+    assert_eq!(suite.ledger(ALICE, ATOM), Uint128::new(1_000));
+    assert_eq!(suite.ledger(ALICE, USDC), Uint128::new(1_000));
+    assert!(suite.ledger(BOB, suite.lp_token.as_str()).is_zero());
+}
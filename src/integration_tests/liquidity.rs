@@ -0,0 +1,708 @@
+use cosmwasm_std::{coin, Decimal, DenomMetadata, DenomUnit, Uint128};
+
+use super::*;
+use crate::msg::{Asset, SimulateAddLiquidityResponse};
+use crate::state::PauseCategory;
+
+const WETH: &str = "aweth";
+
+fn add_liquidity_msg(suite: &Suite, coins: Vec<Coin>) -> ExecuteMsg {
+    ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins,
+        slippage_tolerance: Some(Decimal::percent(1)),
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        min_lp_out: None,
+    }
+}
+
+fn remove_liquidity_msg(suite: &Suite, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::RemoveLiquidity {
+        chain_id: CHAIN_ID.to_string(),
+        pair: suite.pair.clone(),
+        amount: Uint128::new(amount),
+        receiver: EVM_RECEIVER.to_string(),
+        min_assets_out: vec![],
+        receive_asset: None,
+        delivery: Delivery::Bridge,
+    }
+}
+
+#[test]
+fn add_liquidity_with_both_assets() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+
+    let msg = add_liquidity_msg(&suite, vec![coin(1_000, ATOM), coin(1_000, USDC)]);
+    suite.execute_as_owner(msg).unwrap();
+
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(2_000));
+    assert_eq!(suite.lp_token_balance(&suite.trader), Uint128::new(2_000));
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::zero());
+    assert_eq!(suite.balance(&suite.trader, USDC), Uint128::zero());
+}
+
+#[test]
+fn add_liquidity_single_sided_xyk_zap() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(10_000, ATOM)]);
+
+    let msg = add_liquidity_msg(&suite, vec![coin(10_000, ATOM)]);
+    suite.execute_as_owner(msg).unwrap();
+
+    // Part of the ATOM was swapped to USDC and both sides were provided.
+    let lp_token = suite.lp_token.to_string();
+    let minted = suite.ledger(EVM_RECEIVER, &lp_token);
+    assert!(!minted.is_zero());
+    assert_eq!(suite.lp_token_balance(&suite.trader), minted);
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::zero());
+    assert_eq!(suite.balance(&suite.trader, USDC), Uint128::zero());
+}
+
+#[test]
+fn xyk_zap_swaps_to_the_pool_ratio() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(10_000, ATOM)]);
+
+    let msg = add_liquidity_msg(&suite, vec![coin(10_000, ATOM)]);
+    let res = suite.execute_as_owner(msg).unwrap();
+
+    // s = R * (sqrt((1 + r)^2 + 4 * r * a / R) - (1 + r)) / (2 * r) swaps 4_995 of the 10_000
+    // ATOM for 4_955 USDC, so 5_005 ATOM and 4_955 USDC go in at the new pool ratio. The
+    // previous formula swapped nothing and provided the ATOM alone.
+    let returned = res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "return_amount")
+        .map(|attr| attr.value.as_str());
+    assert_eq!(returned, Some("4955"));
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(9_960));
+}
+
+#[test]
+fn add_liquidity_single_sided_stable_zap() {
+    let mut suite = Suite::with_pair_type(PairType::Stable {});
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+
+    // Balanced reserves take about half of the input, a little more to cover the fee.
+    let simulation: SimulateAddLiquidityResponse = suite.query(&QueryMsg::SimulateAddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(1_000, ATOM)],
+        slippage_tolerance: None,
+    });
+    let swap_amount = simulation.swap.unwrap().offer_amount.u128();
+    assert!((500..505).contains(&swap_amount), "{swap_amount}");
+
+    let msg = add_liquidity_msg(&suite, vec![coin(1_000, ATOM)]);
+    suite.execute_as_owner(msg).unwrap();
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), simulation.lp_amount);
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::zero());
+}
+
+#[test]
+fn stable_zap_normalizes_precisions() {
+    let mut suite = Suite::new();
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let (pair, _) = suite.add_pair(vec![native(ATOM), native(WETH)], PairType::Stable {});
+    // One ATOM at the default 6 decimals against one WETH at 18.
+    suite.app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .set_denom_metadata(
+                storage,
+                WETH.to_string(),
+                DenomMetadata {
+                    base: WETH.to_string(),
+                    denom_units: vec![
+                        DenomUnit {
+                            denom: WETH.to_string(),
+                            exponent: 0,
+                            aliases: vec![],
+                        },
+                        DenomUnit {
+                            denom: "weth".to_string(),
+                            exponent: 18,
+                            aliases: vec![],
+                        },
+                    ],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        router
+            .bank
+            .init_balance(
+                storage,
+                &pair,
+                vec![coin(POOL_RESERVE, ATOM), coin(10u128.pow(18), WETH)],
+            )
+            .unwrap();
+    });
+
+    // The reserves are balanced once scaled, so about half of the input is swapped.
+    let simulation: SimulateAddLiquidityResponse = suite.query(&QueryMsg::SimulateAddLiquidity {
+        pair,
+        coins: vec![coin(1_000, ATOM)],
+        slippage_tolerance: None,
+    });
+    let swap_amount = simulation.swap.unwrap().offer_amount.u128();
+    assert!((500..505).contains(&swap_amount), "{swap_amount}");
+}
+
+#[test]
+fn zap_into_empty_pool_fails() {
+    let mut suite = Suite::new();
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let (pair, _) = suite.add_pair(vec![native(ATOM), native(USDC)], PairType::Xyk {});
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+
+    let mut msg = add_liquidity_msg(&suite, vec![coin(1_000, ATOM)]);
+    if let ExecuteMsg::AddLiquidity { pair: target, .. } = &mut msg {
+        *target = pair;
+    }
+    // Nothing can be priced against empty reserves, so nothing is swapped.
+    suite.execute_as_owner(msg).unwrap_err();
+    assert_eq!(suite.ledger(EVM_RECEIVER, ATOM), Uint128::new(1_000));
+}
+
+#[test]
+fn zap_enforces_slippage_and_min_lp_out() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(10_000, ATOM)]);
+
+    // The swap leg carries the caller's tolerance as its max spread.
+    let msg = ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(10_000, ATOM)],
+        slippage_tolerance: Some(Decimal::permille(1)),
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        min_lp_out: None,
+    };
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(format!("{err:?}").contains("max spread"), "{err:?}");
+
+    let simulation: SimulateAddLiquidityResponse = suite.query(&QueryMsg::SimulateAddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(10_000, ATOM)],
+        slippage_tolerance: Some(Decimal::percent(1)),
+    });
+    let msg = ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(10_000, ATOM)],
+        slippage_tolerance: Some(Decimal::percent(1)),
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: true,
+        min_lp_out: Some(simulation.lp_amount + Uint128::one()),
+    };
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::NotEnoughLpOut {})
+    ));
+
+    let msg = ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(10_000, ATOM)],
+        slippage_tolerance: Some(Decimal::percent(1)),
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        min_lp_out: Some(simulation.lp_amount),
+    };
+    suite.execute_as_owner(msg).unwrap();
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), simulation.lp_amount);
+}
+
+#[test]
+fn add_liquidity_below_min_lp_out() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+
+    let msg = ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(1_000, ATOM), coin(1_000, USDC)],
+        slippage_tolerance: None,
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        min_lp_out: Some(Uint128::new(2_001)),
+    };
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::NotEnoughLpOut {})
+    ));
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::new(1_000));
+}
+
+#[test]
+fn add_liquidity_credits_leftovers() {
+    let mut suite = Suite::with_refunding_pair();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_200, USDC)]);
+
+    let simulation: SimulateAddLiquidityResponse = suite.query(&QueryMsg::SimulateAddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(1_000, ATOM), coin(1_200, USDC)],
+        slippage_tolerance: None,
+    });
+    let msg = ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(1_000, ATOM), coin(1_200, USDC)],
+        slippage_tolerance: None,
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        min_lp_out: None,
+    };
+    suite.execute_as_owner(msg).unwrap();
+
+    // The pair took 1:1 and sent back the extra USDC, which belongs to the depositor.
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(2_000));
+    assert_eq!(simulation.lp_amount, Uint128::new(2_000));
+    assert_eq!(suite.ledger(EVM_RECEIVER, USDC), Uint128::new(200));
+    assert_eq!(suite.ledger(EVM_RECEIVER, ATOM), Uint128::zero());
+    assert_eq!(suite.balance(&suite.trader, USDC), Uint128::new(200));
+}
+
+#[test]
+fn zap_provides_only_its_own_coins() {
+    let mut suite = Suite::with_refunding_pair();
+    // USDC held for another user must stay untouched by the zap.
+    suite.deposit(EVM_RECEIVER, &[coin(10_000, ATOM)]);
+    suite.deposit("other", &[coin(5_000, USDC)]);
+
+    let msg = add_liquidity_msg(&suite, vec![coin(10_000, ATOM)]);
+    suite.execute_as_owner(msg).unwrap();
+
+    let lp_token = suite.lp_token.to_string();
+    assert!(!suite.ledger(EVM_RECEIVER, &lp_token).is_zero());
+    assert_eq!(suite.ledger("other", USDC), Uint128::new(5_000));
+    // Dust sent back by the pair is credited, so holdings match the ledger.
+    assert_eq!(
+        suite.balance(&suite.trader, USDC),
+        Uint128::new(5_000) + suite.ledger(EVM_RECEIVER, USDC)
+    );
+    assert_eq!(
+        suite.balance(&suite.trader, ATOM),
+        suite.ledger(EVM_RECEIVER, ATOM)
+    );
+}
+
+#[test]
+fn add_liquidity_single_sided_custom_zap() {
+    let mut suite = Suite::with_pair_type(PairType::Custom("concentrated".to_string()));
+    suite.deposit(EVM_RECEIVER, &[coin(10_000, ATOM)]);
+
+    let msg = add_liquidity_msg(&suite, vec![coin(10_000, ATOM)]);
+    let res = suite.execute_as_owner(msg).unwrap();
+
+    // Bisecting over the pair's simulation, to within a basis point of the input, swaps
+    // what the XYK closed form does against the mock's constant-product pricing.
+    let returned = res
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "return_amount")
+        .map(|attr| attr.value.as_str());
+    assert_eq!(returned, Some("4955"));
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(9_960));
+    assert_eq!(suite.balance(&suite.trader, USDC), Uint128::zero());
+
+    let simulated: SimulateAddLiquidityResponse = suite.query(&QueryMsg::SimulateAddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(10_000, ATOM)],
+        slippage_tolerance: Some(Decimal::percent(1)),
+    });
+    assert!(simulated.swap.is_some());
+}
+
+#[test]
+fn remove_liquidity_bridges_both_assets() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+    let msg = add_liquidity_msg(&suite, vec![coin(1_000, ATOM), coin(1_000, USDC)]);
+    suite.execute_as_owner(msg).unwrap();
+
+    let msg = remove_liquidity_msg(&suite, 1_000);
+    suite.execute_as_owner(msg).unwrap();
+
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(1_000));
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![send_tx(coin(500, ATOM)), send_tx(coin(500, USDC))]
+    );
+}
+
+#[test]
+fn remove_liquidity_below_min_assets_out() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+    let msg = add_liquidity_msg(&suite, vec![coin(1_000, ATOM), coin(1_000, USDC)]);
+    suite.execute_as_owner(msg).unwrap();
+
+    let mut msg = ExecuteMsg::RemoveLiquidity {
+        chain_id: CHAIN_ID.to_string(),
+        pair: suite.pair.clone(),
+        amount: Uint128::new(1_000),
+        receiver: EVM_RECEIVER.to_string(),
+        min_assets_out: vec![coin(500, ATOM), coin(501, USDC)],
+        receive_asset: None,
+        delivery: Delivery::Bridge,
+    };
+    let err = suite.execute_as_owner(msg.clone()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::NotEnoughAssetsOut { denom, .. }) if denom == USDC
+    ));
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(2_000));
+    assert!(suite.take_send_txs().is_empty());
+
+    if let ExecuteMsg::RemoveLiquidity { min_assets_out, .. } = &mut msg {
+        *min_assets_out = vec![coin(500, ATOM), coin(500, USDC)];
+    }
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![send_tx(coin(500, ATOM)), send_tx(coin(500, USDC))]
+    );
+}
+
+#[test]
+fn remove_liquidity_into_single_asset() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+    let msg = add_liquidity_msg(&suite, vec![coin(1_000, ATOM), coin(1_000, USDC)]);
+    suite.execute_as_owner(msg).unwrap();
+
+    let msg = ExecuteMsg::RemoveLiquidity {
+        chain_id: CHAIN_ID.to_string(),
+        pair: suite.pair.clone(),
+        amount: Uint128::new(1_000),
+        receiver: EVM_RECEIVER.to_string(),
+        min_assets_out: vec![],
+        receive_asset: Some(PADEX.to_string()),
+        delivery: Delivery::Bridge,
+    };
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidReceiveAsset { .. })
+    ));
+
+    // The withdrawn ATOM is swapped through the pair and one USDC coin is bridged.
+    let msg = ExecuteMsg::RemoveLiquidity {
+        chain_id: CHAIN_ID.to_string(),
+        pair: suite.pair.clone(),
+        amount: Uint128::new(1_000),
+        receiver: EVM_RECEIVER.to_string(),
+        min_assets_out: vec![],
+        receive_asset: Some(USDC.to_string()),
+        delivery: Delivery::Bridge,
+    };
+    suite.execute_as_owner(msg).unwrap();
+    let send_txs = suite.take_send_txs();
+    assert_eq!(send_txs.len(), 1);
+    let SendTx { amount, .. } = &send_txs[0];
+    let bridged: Coin = amount.parse().unwrap();
+    assert_eq!(bridged.denom, USDC);
+    assert!(bridged.amount > Uint128::new(990) && bridged.amount < Uint128::new(1_000));
+    // Skyway is recorded only, so the bridged coin is all the contract holds.
+    assert_eq!(suite.balance(&suite.trader, ATOM), Uint128::zero());
+    assert_eq!(suite.balance(&suite.trader, USDC), bridged.amount);
+}
+
+#[test]
+fn zap_out_floors_the_delivered_coin() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+    let msg = add_liquidity_msg(&suite, vec![coin(1_000, ATOM), coin(1_000, USDC)]);
+    suite.execute_as_owner(msg).unwrap();
+
+    // 500 USDC are withdrawn and the 500 ATOM swapped into a bit less than 500 more.
+    let mut msg = ExecuteMsg::RemoveLiquidity {
+        chain_id: CHAIN_ID.to_string(),
+        pair: suite.pair.clone(),
+        amount: Uint128::new(1_000),
+        receiver: EVM_RECEIVER.to_string(),
+        min_assets_out: vec![coin(1_000, USDC)],
+        receive_asset: Some(USDC.to_string()),
+        delivery: Delivery::Bridge,
+    };
+    let err = suite.execute_as_owner(msg.clone()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::NotEnoughAssetsOut { denom, received, .. })
+            if denom == USDC && *received > Uint128::new(990)
+    ));
+    assert!(suite.take_send_txs().is_empty());
+
+    if let ExecuteMsg::RemoveLiquidity { min_assets_out, .. } = &mut msg {
+        *min_assets_out = vec![coin(990, USDC)];
+    }
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(suite.take_send_txs().len(), 1);
+}
+
+#[test]
+fn remove_liquidity_without_bridging() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(1_000, USDC)]);
+    let msg = add_liquidity_msg(&suite, vec![coin(1_000, ATOM), coin(1_000, USDC)]);
+    suite.execute_as_owner(msg).unwrap();
+    // Neither mode bridges, so they work while bridging is paused.
+    suite
+        .execute_as_owner(ExecuteMsg::Pause {
+            category: PauseCategory::Bridging,
+        })
+        .unwrap();
+
+    let mut msg = remove_liquidity_msg(&suite, 1_000);
+    if let ExecuteMsg::RemoveLiquidity { delivery, .. } = &mut msg {
+        *delivery = Delivery::Credit;
+    }
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(suite.ledger(EVM_RECEIVER, ATOM), Uint128::new(500));
+    assert_eq!(suite.ledger(EVM_RECEIVER, USDC), Uint128::new(500));
+
+    let user = suite.user.clone();
+    let mut msg = remove_liquidity_msg(&suite, 1_000);
+    if let ExecuteMsg::RemoveLiquidity { delivery, .. } = &mut msg {
+        *delivery = Delivery::Send {
+            address: user.to_string(),
+        };
+    }
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(suite.balance(&user, ATOM), Uint128::new(500));
+    assert_eq!(suite.balance(&user, USDC), Uint128::new(500));
+    assert!(suite.take_send_txs().is_empty());
+
+    let lp_token = suite.lp_token.to_string();
+    assert!(suite.ledger(EVM_RECEIVER, &lp_token).is_zero());
+}
+
+#[test]
+fn remove_liquidity_above_ledger_balance() {
+    let mut suite = Suite::new();
+    let lp_token = suite.lp_token.to_string();
+    suite.mint_lp_to_trader(1_000);
+    suite.credit(EVM_RECEIVER, &lp_token, 1_000);
+
+    let msg = remove_liquidity_msg(&suite, 1_001);
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientLiquidity {})
+    ));
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), Uint128::new(1_000));
+}
+
+#[test]
+fn remove_liquidity_from_cw20_pair() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let token_code = suite.app.store_code(mocks::cw20_token::contract());
+    let token = suite
+        .app
+        .instantiate_contract(
+            token_code,
+            suite.owner.clone(),
+            &mocks::cw20_token::InstantiateMsg {},
+            &[],
+            "token",
+            None,
+        )
+        .unwrap();
+    let native = |denom: &str| AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    let cw20_info = AssetInfo::Token {
+        contract_addr: token.clone(),
+    };
+    let (pair, lp_token) = suite.add_pair(
+        vec![native(ATOM), native(USDC), cw20_info.clone()],
+        PairType::Xyk {},
+    );
+    // Seed a three-asset pool whose LP shares all belong to the trader.
+    suite
+        .app
+        .execute_contract(
+            suite.faucet.clone(),
+            token.clone(),
+            &cw20::Cw20ExecuteMsg::Mint {
+                recipient: pair.to_string(),
+                amount: Uint128::new(900),
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+        .app
+        .execute_contract(
+            suite.faucet.clone(),
+            pair.clone(),
+            &mocks::pair::ExecuteMsg::ProvideLiquidity {
+                assets: vec![
+                    Asset {
+                        info: native(ATOM),
+                        amount: Uint128::new(300),
+                    },
+                    Asset {
+                        info: native(USDC),
+                        amount: Uint128::new(300),
+                    },
+                    Asset {
+                        info: cw20_info,
+                        amount: Uint128::new(900),
+                    },
+                ],
+                slippage_tolerance: None,
+                receiver: Some(suite.trader.to_string()),
+            },
+            &[coin(300, ATOM), coin(300, USDC)],
+        )
+        .unwrap();
+    let lp_token = lp_token.to_string();
+    suite.credit(EVM_RECEIVER, &lp_token, 1_500);
+
+    let mut msg = ExecuteMsg::RemoveLiquidity {
+        chain_id: CHAIN_ID.to_string(),
+        pair,
+        amount: Uint128::new(1_500),
+        receiver: EVM_RECEIVER.to_string(),
+        min_assets_out: vec![],
+        receive_asset: Some(USDC.to_string()),
+        delivery: Delivery::Bridge,
+    };
+    let err = suite.execute_as_owner(msg.clone()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::UnsupportedZapOut {})
+    ));
+
+    if let ExecuteMsg::RemoveLiquidity {
+        min_assets_out,
+        receive_asset,
+        ..
+    } = &mut msg
+    {
+        *min_assets_out = vec![coin(901, token.as_str())];
+        *receive_asset = None;
+    }
+    let err = suite.execute_as_owner(msg.clone()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::NotEnoughAssetsOut { denom, .. }) if denom == token.as_str()
+    ));
+
+    // Natives are bridged and the cw20 output, which Skyway cannot carry, is credited.
+    if let ExecuteMsg::RemoveLiquidity { min_assets_out, .. } = &mut msg {
+        *min_assets_out = vec![coin(900, token.as_str())];
+    }
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![send_tx(coin(300, ATOM)), send_tx(coin(300, USDC))]
+    );
+    assert_eq!(
+        suite.ledger(EVM_RECEIVER, token.as_str()),
+        Uint128::new(900)
+    );
+    assert!(suite.ledger(EVM_RECEIVER, &lp_token).is_zero());
+}
+
+#[test]
+fn simulate_liquidity_matches_execution() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    suite.deposit(EVM_RECEIVER, &[coin(10_000, ATOM)]);
+
+    let simulation: SimulateAddLiquidityResponse = suite.query(&QueryMsg::SimulateAddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(10_000, ATOM)],
+        slippage_tolerance: None,
+    });
+    let swap = simulation.swap.unwrap();
+    assert!(!swap.offer_amount.is_zero() && swap.offer_amount < Uint128::new(10_000));
+    assert!(swap.commission_amount.is_some());
+
+    let msg = add_liquidity_msg(&suite, vec![coin(10_000, ATOM)]);
+    suite.execute_as_owner(msg).unwrap();
+    let lp_token = suite.lp_token.to_string();
+    assert_eq!(suite.ledger(EVM_RECEIVER, &lp_token), simulation.lp_amount);
+
+    let assets: Vec<Asset> = suite.query(&QueryMsg::SimulateRemoveLiquidity {
+        pair: suite.pair.clone(),
+        amount: Uint128::new(1_000),
+    });
+    let msg = remove_liquidity_msg(&suite, 1_000);
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(
+        suite.take_send_txs(),
+        vec![
+            send_tx(coin(assets[0].amount.u128(), ATOM)),
+            send_tx(coin(assets[1].amount.u128(), USDC)),
+        ]
+    );
+}
+
+#[test]
+fn failed_provision_refunds_into_ledger() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM), coin(500, USDC)]);
+
+    // A 2:1 deposit into a 1:1 pool exceeds the 1% slippage tolerance.
+    let msg = ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(1_000, ATOM), coin(500, USDC)],
+        slippage_tolerance: Some(Decimal::percent(1)),
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        min_lp_out: None,
+    };
+    suite.execute_as_owner(msg).unwrap_err();
+
+    let msg = ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(1_000, ATOM), coin(500, USDC)],
+        slippage_tolerance: Some(Decimal::percent(1)),
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: true,
+        min_lp_out: None,
+    };
+    suite.execute_as_owner(msg).unwrap();
+    assert_eq!(suite.ledger(EVM_RECEIVER, ATOM), Uint128::new(1_000));
+    assert_eq!(suite.ledger(EVM_RECEIVER, USDC), Uint128::new(500));
+    assert!(suite.lp_token_balance(&suite.trader).is_zero());
+
+    // Coins beyond the depositor's ledger balance are not provided.
+    let msg = ExecuteMsg::AddLiquidity {
+        pair: suite.pair.clone(),
+        coins: vec![coin(1_000, ATOM), coin(1_000, USDC)],
+        slippage_tolerance: None,
+        depositor: EVM_RECEIVER.to_string(),
+        refund_on_failure: true,
+        min_lp_out: None,
+    };
+    let err = suite.execute_as_owner(msg).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientBalance {})
+    ));
+    assert_eq!(suite.ledger(EVM_RECEIVER, USDC), Uint128::new(500));
+}
@@ -0,0 +1,815 @@
+//! Minimal stand-ins for the Palomadex contracts the trader talks to.
+//!
+//! They implement just enough of the real interfaces for the trader's
+//! sub-messages and queries to round-trip inside `cw-multi-test`.
+
+use cosmwasm_std::{Empty, StdError};
+use cw_multi_test::{Contract, ContractWrapper};
+
+use crate::msg::PalomaMsg;
+
+type MockResult = Result<cosmwasm_std::Response, StdError>;
+
+/// cw20 LP token with unrestricted minting.
+pub mod cw20_token {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    };
+    use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+    use cw_storage_plus::Map;
+
+    use super::*;
+
+    const BALANCES: Map<&str, Uint128> = Map::new("balances");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {}
+
+    fn move_tokens(deps: DepsMut, from: &str, to: &str, amount: Uint128) -> StdResult<()> {
+        BALANCES.update(deps.storage, from, |balance| {
+            balance
+                .unwrap_or_default()
+                .checked_sub(amount)
+                .map_err(StdError::overflow)
+        })?;
+        BALANCES.update(deps.storage, to, |balance| -> StdResult<_> {
+            Ok(balance.unwrap_or_default() + amount)
+        })?;
+        Ok(())
+    }
+
+    pub fn instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: InstantiateMsg,
+    ) -> MockResult {
+        Ok(Response::new())
+    }
+
+    pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: Cw20ExecuteMsg) -> MockResult {
+        match msg {
+            Cw20ExecuteMsg::Mint { recipient, amount } => {
+                BALANCES.update(deps.storage, &recipient, |balance| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default() + amount)
+                })?;
+                Ok(Response::new())
+            }
+            Cw20ExecuteMsg::Transfer { recipient, amount } => {
+                move_tokens(deps, info.sender.as_str(), &recipient, amount)?;
+                Ok(Response::new())
+            }
+            Cw20ExecuteMsg::Burn { amount } => {
+                BALANCES.update(deps.storage, info.sender.as_str(), |balance| {
+                    balance
+                        .unwrap_or_default()
+                        .checked_sub(amount)
+                        .map_err(StdError::overflow)
+                })?;
+                Ok(Response::new())
+            }
+            Cw20ExecuteMsg::Send {
+                contract,
+                amount,
+                msg,
+            } => {
+                move_tokens(deps, info.sender.as_str(), &contract, amount)?;
+                Ok(Response::new().add_message(
+                    Cw20ReceiveMsg {
+                        sender: info.sender.to_string(),
+                        amount,
+                        msg,
+                    }
+                    .into_cosmos_msg(contract)?,
+                ))
+            }
+            _ => Err(StdError::generic_err("unsupported cw20 message")),
+        }
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: Cw20QueryMsg) -> StdResult<Binary> {
+        match msg {
+            Cw20QueryMsg::Balance { address } => to_json_binary(&BalanceResponse {
+                balance: BALANCES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            }),
+            _ => Err(StdError::generic_err("unsupported cw20 query")),
+        }
+    }
+
+    pub fn contract() -> Box<dyn Contract<PalomaMsg, Empty>> {
+        Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+    }
+}
+
+/// Constant-product pair holding native and cw20 reserves.
+///
+/// Swaps are native only. LP shares are minted one-for-one with the sum of the provided
+/// assets, cw20 assets counting as already transferred to the pair,
+/// and the pair answers `FeeInfo` itself by reporting its own address as the
+/// factory.
+pub mod pair {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        coin, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env,
+        MessageInfo, Response, StdResult, Uint128, WasmMsg,
+    };
+    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw_storage_plus::Item;
+
+    use super::*;
+    use crate::msg::{
+        Asset, AssetInfo, ConfigResponse, CumulativePricesResponse, ExternalExecuteMsg,
+        ExternalQueryMsg, FeeInfoResponse, PairInfo, PairType, PoolResponse, SimulationResponse,
+        StablePoolConfig, SwapOperation,
+    };
+
+    pub const TOTAL_FEE_BPS: u16 = 30;
+    /// Amplification reported to the trader for stable pairs; swaps stay constant-product.
+    pub const STABLE_AMP: u64 = 10;
+    /// Scale of accumulated prices, 10^TWAP_PRECISION as in real pairs
+    const TWAP_SCALE: u128 = 1_000_000;
+
+    const PAIR_INFO: Item<PairInfo> = Item::new("pair_info");
+    const TOTAL_SHARE: Item<Uint128> = Item::new("total_share");
+    const REFUND_EXCESS: Item<bool> = Item::new("refund_excess");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub asset_infos: Vec<AssetInfo>,
+        pub liquidity_token: Addr,
+        pub pair_type: PairType,
+        /// Take two-sided deposits at the pool ratio and send the excess back
+        pub refund_excess: bool,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        ExecuteSwapOperations {
+            operations: Vec<SwapOperation>,
+            minimum_receive: Option<Uint128>,
+            to: Option<String>,
+            max_spread: Option<cosmwasm_std::Decimal>,
+        },
+        ProvideLiquidity {
+            assets: Vec<Asset>,
+            slippage_tolerance: Option<cosmwasm_std::Decimal>,
+            receiver: Option<String>,
+        },
+        Receive(Cw20ReceiveMsg),
+    }
+
+    fn denom(info: &AssetInfo) -> StdResult<String> {
+        match info {
+            AssetInfo::NativeToken { denom } => Ok(denom.clone()),
+            AssetInfo::Token { .. } => Err(StdError::generic_err("native reserves only")),
+        }
+    }
+
+    fn reserve(deps: Deps, env: &Env, info: &AssetInfo) -> StdResult<Uint128> {
+        match info {
+            AssetInfo::NativeToken { denom } => Ok(deps
+                .querier
+                .query_balance(&env.contract.address, denom)?
+                .amount),
+            AssetInfo::Token { contract_addr } => {
+                let balance: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+                    contract_addr,
+                    &cw20::Cw20QueryMsg::Balance {
+                        address: env.contract.address.to_string(),
+                    },
+                )?;
+                Ok(balance.balance)
+            }
+        }
+    }
+
+    /// Return, spread and commission of swapping `offer_amount` against the reserves.
+    fn swap(
+        reserve_in: Uint128,
+        reserve_out: Uint128,
+        offer_amount: Uint128,
+    ) -> SimulationResponse {
+        let gross = reserve_out.multiply_ratio(offer_amount, reserve_in + offer_amount);
+        let return_amount = gross.multiply_ratio(10000 - TOTAL_FEE_BPS, 10000u16);
+        SimulationResponse {
+            return_amount,
+            spread_amount: reserve_out.multiply_ratio(offer_amount, reserve_in) - gross,
+            commission_amount: gross - return_amount,
+        }
+    }
+
+    /// Amounts of a two-sided deposit taken at the ratio of reserves `r0` and `r1`.
+    fn accepted(r0: Uint128, r1: Uint128, a0: Uint128, a1: Uint128) -> (Uint128, Uint128) {
+        if r0.is_zero() || r1.is_zero() {
+            (a0, a1)
+        } else if a0 * r1 > a1 * r0 {
+            (a1.multiply_ratio(r0, r1), a1)
+        } else {
+            (a0, a0.multiply_ratio(r1, r0))
+        }
+    }
+
+    fn share(
+        deps: Deps,
+        env: &Env,
+        pair_info: &PairInfo,
+        amount: Uint128,
+    ) -> StdResult<Vec<Asset>> {
+        let total_share = TOTAL_SHARE.load(deps.storage)?;
+        pair_info
+            .asset_infos
+            .iter()
+            .map(|asset_info| {
+                Ok(Asset {
+                    info: asset_info.clone(),
+                    amount: reserve(deps, env, asset_info)?.multiply_ratio(amount, total_share),
+                })
+            })
+            .collect()
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> MockResult {
+        PAIR_INFO.save(
+            deps.storage,
+            &PairInfo {
+                asset_infos: msg.asset_infos,
+                contract_addr: env.contract.address,
+                liquidity_token: msg.liquidity_token,
+                pair_type: msg.pair_type,
+            },
+        )?;
+        TOTAL_SHARE.save(deps.storage, &Uint128::zero())?;
+        REFUND_EXCESS.save(deps.storage, &msg.refund_excess)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> MockResult {
+        let pair_info = PAIR_INFO.load(deps.storage)?;
+        match msg {
+            ExecuteMsg::ExecuteSwapOperations {
+                operations,
+                to,
+                max_spread,
+                ..
+            } => {
+                let operation = operations
+                    .last()
+                    .ok_or_else(|| StdError::generic_err("no operations"))?;
+                let offer_asset_info = &operation.offer_asset_info();
+                let ask_asset_info = &operation.ask_asset_info();
+                let offer_amount = info.funds[0].amount;
+                let reserve_in = reserve(deps.as_ref(), &env, offer_asset_info)? - offer_amount;
+                let reserve_out = reserve(deps.as_ref(), &env, ask_asset_info)?;
+                let simulation = swap(reserve_in, reserve_out, offer_amount);
+                if let Some(max_spread) = max_spread {
+                    let spread = cosmwasm_std::Decimal::from_ratio(
+                        simulation.spread_amount,
+                        simulation.return_amount + simulation.spread_amount,
+                    );
+                    if spread > max_spread {
+                        return Err(StdError::generic_err("Operation exceeds max spread limit"));
+                    }
+                }
+                let return_amount = simulation.return_amount;
+                Ok(Response::new()
+                    .add_message(BankMsg::Send {
+                        to_address: to.unwrap_or_else(|| info.sender.to_string()),
+                        amount: vec![coin(return_amount.u128(), denom(ask_asset_info)?)],
+                    })
+                    .add_attribute("return_amount", return_amount))
+            }
+            ExecuteMsg::ProvideLiquidity {
+                assets,
+                slippage_tolerance,
+                receiver,
+            } => {
+                if let (Some(tolerance), [a0, a1]) = (slippage_tolerance, &assets[..]) {
+                    // Reserves before this deposit, whose funds are already in the balance.
+                    let r0 = reserve(deps.as_ref(), &env, &a0.info)? - a0.amount;
+                    let r1 = reserve(deps.as_ref(), &env, &a1.info)? - a1.amount;
+                    let ratio = cosmwasm_std::Decimal::from_ratio(a0.amount * r1, a1.amount * r0);
+                    if ratio.abs_diff(cosmwasm_std::Decimal::one()) > tolerance {
+                        return Err(StdError::generic_err(
+                            "Operation exceeds max slippage tolerance",
+                        ));
+                    }
+                }
+                let mut funds = info.funds;
+                let mut excess = vec![];
+                if let (true, [a0, a1]) = (REFUND_EXCESS.load(deps.storage)?, &assets[..]) {
+                    let r0 = reserve(deps.as_ref(), &env, &a0.info)? - a0.amount;
+                    let r1 = reserve(deps.as_ref(), &env, &a1.info)? - a1.amount;
+                    let (x0, x1) = accepted(r0, r1, a0.amount, a1.amount);
+                    funds = vec![
+                        coin(x0.u128(), denom(&a0.info)?),
+                        coin(x1.u128(), denom(&a1.info)?),
+                    ];
+                    excess = [(a0, x0), (a1, x1)]
+                        .into_iter()
+                        .filter(|(asset, taken)| asset.amount > *taken)
+                        .map(|(asset, taken)| {
+                            Ok(coin((asset.amount - taken).u128(), denom(&asset.info)?))
+                        })
+                        .collect::<StdResult<_>>()?;
+                }
+                let share = assets
+                    .iter()
+                    .filter(|asset| matches!(asset.info, AssetInfo::Token { .. }))
+                    .map(|asset| asset.amount)
+                    .chain(funds.iter().map(|coin| coin.amount))
+                    .fold(Uint128::zero(), |total, amount| total + amount);
+                TOTAL_SHARE.update(deps.storage, |total| -> StdResult<_> { Ok(total + share) })?;
+                let mut response = Response::new();
+                if !excess.is_empty() {
+                    response = response.add_message(BankMsg::Send {
+                        to_address: info.sender.to_string(),
+                        amount: excess,
+                    });
+                }
+                Ok(response.add_message(WasmMsg::Execute {
+                    contract_addr: pair_info.liquidity_token.to_string(),
+                    msg: to_json_binary(&Cw20ExecuteMsg::Mint {
+                        recipient: receiver.unwrap_or_else(|| info.sender.to_string()),
+                        amount: share,
+                    })?,
+                    funds: vec![],
+                }))
+            }
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender,
+                amount,
+                msg,
+            }) => {
+                if info.sender != pair_info.liquidity_token {
+                    return Err(StdError::generic_err("unknown cw20"));
+                }
+                let ExternalExecuteMsg::WithdrawLiquidity { .. } = from_json(msg)? else {
+                    return Err(StdError::generic_err("unsupported hook"));
+                };
+                let total_share = TOTAL_SHARE.load(deps.storage)?;
+                let refund = share(deps.as_ref(), &env, &pair_info, amount)?;
+                TOTAL_SHARE.save(deps.storage, &(total_share - amount))?;
+                let mut natives: Vec<Coin> = vec![];
+                let mut response = Response::new();
+                for asset in refund {
+                    match asset.info {
+                        AssetInfo::NativeToken { denom } => {
+                            if !asset.amount.is_zero() {
+                                natives.push(coin(asset.amount.u128(), denom))
+                            }
+                        }
+                        AssetInfo::Token { contract_addr } => {
+                            response = response.add_message(WasmMsg::Execute {
+                                contract_addr: contract_addr.to_string(),
+                                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                                    recipient: sender.clone(),
+                                    amount: asset.amount,
+                                })?,
+                                funds: vec![],
+                            })
+                        }
+                    }
+                }
+                if !natives.is_empty() {
+                    response = response.add_message(BankMsg::Send {
+                        to_address: sender,
+                        amount: natives,
+                    });
+                }
+                Ok(response)
+            }
+        }
+    }
+
+    pub fn query(deps: Deps, env: Env, msg: ExternalQueryMsg) -> StdResult<Binary> {
+        let pair_info = PAIR_INFO.load(deps.storage)?;
+        match msg {
+            ExternalQueryMsg::Pair {} => to_json_binary(&pair_info),
+            ExternalQueryMsg::Pool {} => to_json_binary(&PoolResponse {
+                assets: pair_info
+                    .asset_infos
+                    .iter()
+                    .map(|info| {
+                        Ok(Asset {
+                            info: info.clone(),
+                            amount: reserve(deps, &env, info)?,
+                        })
+                    })
+                    .collect::<StdResult<Vec<_>>>()?,
+                total_share: TOTAL_SHARE.load(deps.storage)?,
+            }),
+            ExternalQueryMsg::Config {} => to_json_binary(&ConfigResponse {
+                block_time_last: env.block.time.seconds(),
+                params: match pair_info.pair_type {
+                    PairType::Stable {} => Some(to_json_binary(&StablePoolConfig {
+                        amp: cosmwasm_std::Decimal::from_atomics(STABLE_AMP, 0).unwrap(),
+                    })?),
+                    _ => None,
+                },
+                owner: env.contract.address.clone(),
+                factory_addr: env.contract.address,
+            }),
+            ExternalQueryMsg::FeeInfo { .. } => to_json_binary(&FeeInfoResponse {
+                fee_address: None,
+                total_fee_bps: TOTAL_FEE_BPS,
+                maker_fee_bps: 0,
+            }),
+            ExternalQueryMsg::Simulation {
+                offer_asset,
+                ask_asset_info,
+            } => {
+                let ask_asset_info =
+                    ask_asset_info.ok_or_else(|| StdError::generic_err("ask asset required"))?;
+                to_json_binary(&swap(
+                    reserve(deps, &env, &offer_asset.info)?,
+                    reserve(deps, &env, &ask_asset_info)?,
+                    offer_asset.amount,
+                ))
+            }
+            ExternalQueryMsg::SimulateProvide { assets, .. } => {
+                let share = match (REFUND_EXCESS.load(deps.storage)?, &assets[..]) {
+                    (true, [a0, a1]) => {
+                        let (x0, x1) = accepted(
+                            reserve(deps, &env, &a0.info)?,
+                            reserve(deps, &env, &a1.info)?,
+                            a0.amount,
+                            a1.amount,
+                        );
+                        x0 + x1
+                    }
+                    _ => assets
+                        .iter()
+                        .fold(Uint128::zero(), |total, asset| total + asset.amount),
+                };
+                to_json_binary(&share)
+            }
+            ExternalQueryMsg::SimulateWithdraw { lp_amount } => {
+                to_json_binary(&share(deps, &env, &pair_info, lp_amount)?)
+            }
+            // Accumulates the current spot price since time zero at the 6 decimals real pairs
+            // use, which is exact while the reserves stay put.
+            ExternalQueryMsg::CumulativePrices {} => {
+                let assets = pair_info
+                    .asset_infos
+                    .iter()
+                    .map(|info| {
+                        Ok(Asset {
+                            info: info.clone(),
+                            amount: reserve(deps, &env, info)?,
+                        })
+                    })
+                    .collect::<StdResult<Vec<_>>>()?;
+                let mut cumulative_prices = vec![];
+                for offer in &assets {
+                    for ask in assets.iter().filter(|ask| ask.info != offer.info) {
+                        let price = ask.amount.multiply_ratio(TWAP_SCALE, offer.amount);
+                        cumulative_prices.push((
+                            offer.info.clone(),
+                            ask.info.clone(),
+                            price * Uint128::from(env.block.time.seconds()),
+                        ));
+                    }
+                }
+                to_json_binary(&CumulativePricesResponse {
+                    assets,
+                    total_share: TOTAL_SHARE.load(deps.storage)?,
+                    cumulative_prices,
+                })
+            }
+            ExternalQueryMsg::SimulateSwapOperations { .. } => {
+                Err(StdError::generic_err("not a router"))
+            }
+        }
+    }
+
+    pub fn contract() -> Box<dyn Contract<PalomaMsg, Empty>> {
+        Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+    }
+}
+
+/// Router paying out the final ask asset at a fixed rate from its own balance.
+pub mod router {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        coin, to_json_binary, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+        Response, StdResult, WasmMsg,
+    };
+    use cw_storage_plus::Item;
+
+    use super::*;
+    use crate::msg::{
+        AssetInfo, ExternalExecuteMsg, ExternalQueryMsg, SimulateSwapOperationsResponse,
+        SwapOperation,
+    };
+
+    const RATE: Item<Decimal> = Item::new("rate");
+    const REPORTED_RATE: Item<Decimal> = Item::new("reported_rate");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub rate: Decimal,
+        /// Rate announced in the `return_amount` attribute, when it differs from `rate`
+        pub reported_rate: Option<Decimal>,
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> MockResult {
+        RATE.save(deps.storage, &msg.rate)?;
+        REPORTED_RATE.save(deps.storage, &msg.reported_rate.unwrap_or(msg.rate))?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExternalExecuteMsg,
+    ) -> MockResult {
+        let ExternalExecuteMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            to,
+            ..
+        } = msg
+        else {
+            return Err(StdError::generic_err("unsupported router message"));
+        };
+        let Some(ask_asset_info) = operations.last().map(SwapOperation::ask_asset_info) else {
+            return Err(StdError::generic_err("empty route"));
+        };
+        let return_amount = info.funds[0].amount.mul_floor(RATE.load(deps.storage)?);
+        if return_amount < minimum_receive.unwrap_or_default() {
+            return Err(StdError::generic_err("minimum receive not reached"));
+        }
+        let to = to.unwrap_or_else(|| info.sender.to_string());
+        let msg: CosmosMsg = match ask_asset_info {
+            AssetInfo::NativeToken { denom } => BankMsg::Send {
+                to_address: to,
+                amount: vec![coin(return_amount.u128(), denom)],
+            }
+            .into(),
+            // cw20 output is minted by the mock token, which allows anyone to mint
+            AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&cw20::Cw20ExecuteMsg::Mint {
+                    recipient: to,
+                    amount: return_amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        };
+        let reported_amount = info.funds[0]
+            .amount
+            .mul_floor(REPORTED_RATE.load(deps.storage)?);
+        Ok(Response::new()
+            .add_message(msg)
+            .add_attribute("return_amount", reported_amount))
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: ExternalQueryMsg) -> StdResult<Binary> {
+        let ExternalQueryMsg::SimulateSwapOperations { offer_amount, .. } = msg else {
+            return Err(StdError::generic_err("unsupported router query"));
+        };
+        to_json_binary(&SimulateSwapOperationsResponse {
+            amount: offer_amount.mul_floor(RATE.load(deps.storage)?),
+        })
+    }
+
+    pub fn contract() -> Box<dyn Contract<PalomaMsg, Empty>> {
+        Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+    }
+}
+
+/// Incentivizer staking LP tokens and paying a fixed PADEX reward per call.
+pub mod incentivizer {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+    use cosmwasm_std::{
+        coin, from_json, to_json_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo,
+        Response, StdResult, Uint128, WasmMsg,
+    };
+    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw_storage_plus::{Item, Map};
+
+    use super::*;
+    use crate::msg::Cw20Msg;
+
+    const REWARD: Item<cosmwasm_std::Coin> = Item::new("reward");
+    const STAKED: Map<(&str, &str), Uint128> = Map::new("staked");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub reward: cosmwasm_std::Coin,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Receive(Cw20ReceiveMsg),
+        Withdraw {
+            lp_token: String,
+            amount: Uint128,
+            user: Option<String>,
+        },
+        ClaimRewards {
+            lp_tokens: Vec<String>,
+            user: Option<String>,
+        },
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(Uint128)]
+        Deposit { lp_token: String, user: String },
+    }
+
+    fn reward_msg(deps: Deps, to: &str) -> StdResult<BankMsg> {
+        let reward = REWARD.load(deps.storage)?;
+        Ok(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![coin(reward.amount.u128(), reward.denom)],
+        })
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> MockResult {
+        REWARD.save(deps.storage, &msg.reward)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> MockResult {
+        match msg {
+            ExecuteMsg::Receive(Cw20ReceiveMsg { amount, msg, .. }) => {
+                let Cw20Msg::Deposit { recipient } = from_json(msg)?;
+                let user = recipient.unwrap_or_default();
+                STAKED.update(
+                    deps.storage,
+                    (info.sender.as_str(), &user),
+                    |staked| -> StdResult<_> { Ok(staked.unwrap_or_default() + amount) },
+                )?;
+                Ok(Response::new())
+            }
+            ExecuteMsg::Withdraw {
+                lp_token,
+                amount,
+                user,
+            } => {
+                let user = user.unwrap_or_default();
+                STAKED.update(deps.storage, (&lp_token, &user), |staked| {
+                    staked
+                        .unwrap_or_default()
+                        .checked_sub(amount)
+                        .map_err(StdError::overflow)
+                })?;
+                Ok(Response::new()
+                    .add_message(WasmMsg::Execute {
+                        contract_addr: lp_token,
+                        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                            recipient: info.sender.to_string(),
+                            amount,
+                        })?,
+                        funds: vec![],
+                    })
+                    .add_message(reward_msg(deps.as_ref(), info.sender.as_str())?))
+            }
+            ExecuteMsg::ClaimRewards { .. } => {
+                Ok(Response::new().add_message(reward_msg(deps.as_ref(), info.sender.as_str())?))
+            }
+        }
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Deposit { lp_token, user } => to_json_binary(
+                &STAKED
+                    .may_load(deps.storage, (&lp_token, &user))?
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    pub fn contract() -> Box<dyn Contract<PalomaMsg, Empty>> {
+        Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+    }
+}
+
+/// vePADEX escrow locking native PADEX per user.
+pub mod vepadex {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+    use cosmwasm_std::{
+        coin, to_json_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+        StdResult, Uint128,
+    };
+    use cw_storage_plus::{Item, Map};
+
+    use super::*;
+    use crate::msg::VePadexExecuteMsg;
+
+    const DENOM: Item<String> = Item::new("denom");
+    const LOCKS: Map<&str, (Uint128, u64)> = Map::new("locks");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub denom: String,
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        /// Locked amount and end lock time
+        #[returns((Uint128, u64))]
+        Lock { user: String },
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> MockResult {
+        DENOM.save(deps.storage, &msg.denom)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: VePadexExecuteMsg,
+    ) -> MockResult {
+        let denom = DENOM.load(deps.storage)?;
+        let paid = info
+            .funds
+            .iter()
+            .filter(|coin| coin.denom == denom)
+            .fold(Uint128::zero(), |total, coin| total + coin.amount);
+        match msg {
+            VePadexExecuteMsg::CreateLock {
+                end_lock_time,
+                user,
+            } => {
+                LOCKS.save(
+                    deps.storage,
+                    &user.unwrap_or_default(),
+                    &(paid, end_lock_time),
+                )?;
+                Ok(Response::new())
+            }
+            VePadexExecuteMsg::IncreaseLockAmount { user } => {
+                LOCKS.update(deps.storage, &user.unwrap_or_default(), |lock| {
+                    let (amount, end) = lock.ok_or_else(|| StdError::generic_err("no lock"))?;
+                    Ok::<_, StdError>((amount + paid, end))
+                })?;
+                Ok(Response::new())
+            }
+            VePadexExecuteMsg::IncreaseEndLockTime {
+                end_lock_time,
+                user,
+            } => {
+                LOCKS.update(deps.storage, &user.unwrap_or_default(), |lock| {
+                    let (amount, _) = lock.ok_or_else(|| StdError::generic_err("no lock"))?;
+                    Ok::<_, StdError>((amount, end_lock_time))
+                })?;
+                Ok(Response::new())
+            }
+            VePadexExecuteMsg::Withdraw { user } => {
+                let user = user.unwrap_or_default();
+                let (amount, _) = LOCKS.load(deps.storage, &user)?;
+                LOCKS.remove(deps.storage, &user);
+                Ok(Response::new().add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: vec![coin(amount.u128(), denom)],
+                }))
+            }
+            VePadexExecuteMsg::Checkpoint {} => Ok(Response::new()),
+        }
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Lock { user } => {
+                to_json_binary(&LOCKS.may_load(deps.storage, &user)?.unwrap_or_default())
+            }
+        }
+    }
+
+    pub fn contract() -> Box<dyn Contract<PalomaMsg, Empty>> {
+        Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+    }
+}
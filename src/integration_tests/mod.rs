@@ -0,0 +1,478 @@
+//! End-to-end tests running the trader against mock Palomadex contracts.
+
+mod admin;
+mod exchange;
+mod incentives;
+mod ledger;
+mod liquidity;
+mod mocks;
+mod multisig;
+mod pause;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coin, coins, Addr, Api, Binary, BlockInfo, Coin, Decimal, DepsMut, Empty, Env, MessageInfo,
+    Querier, Response, Storage, Uint128,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, CosmosRouter,
+    DistributionKeeper, Executor, GovFailingModule, IbcFailingModule, Module, StakeKeeper,
+    StargateFailing, WasmKeeper,
+};
+use serde::de::DeserializeOwned;
+
+use crate::contract;
+use crate::msg::{
+    AssetInfo, Delivery, ExecuteMsg, InstantiateMsg, MigrateMsg, PairType, PalomaMsg, QueryMsg,
+    SendTx,
+};
+use crate::ContractError;
+
+pub const ATOM: &str = "uatom";
+pub const USDC: &str = "uusdc";
+pub const PADEX: &str = "upadex";
+pub const VEPADEX: &str = "uvepadex";
+pub const CHAIN_ID: &str = "ethereum-main";
+pub const EVM_RECEIVER: &str = "0x1111111111111111111111111111111111111111";
+
+/// Initial reserves of each pool asset.
+pub const POOL_RESERVE: u128 = 1_000_000;
+/// Fixed PADEX reward paid by the incentivizer per withdraw/claim.
+pub const PADEX_REWARD: u128 = 100;
+/// Router payout per offered unit.
+pub const ROUTER_RATE: u64 = 2;
+/// Seconds an admin proposal stays open.
+pub const PROPOSAL_TTL: u64 = 3_600;
+
+pub type TraderApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    PalomaRecorder,
+    WasmKeeper<PalomaMsg, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    StargateFailing,
+>;
+
+/// Custom module recording every `PalomaMsg` the trader emits.
+#[derive(Default, Clone)]
+pub struct PalomaRecorder {
+    msgs: Rc<RefCell<Vec<PalomaMsg>>>,
+}
+
+impl Module for PalomaRecorder {
+    type ExecT = PalomaMsg;
+    type QueryT = Empty;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        msg: PalomaMsg,
+    ) -> AnyResult<AppResponse> {
+        self.msgs.borrow_mut().push(msg);
+        Ok(AppResponse::default())
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: Empty,
+    ) -> AnyResult<Binary> {
+        bail!("Unexpected custom query {:?}", request)
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        msg: Empty,
+    ) -> AnyResult<AppResponse> {
+        bail!("Unexpected custom sudo {:?}", msg)
+    }
+}
+
+fn trader_instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response<PalomaMsg>, ContractError> {
+    Ok(contract::instantiate(deps, env, info, msg)?
+        .change_custom()
+        .unwrap())
+}
+
+fn trader_migrate(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+) -> Result<Response<PalomaMsg>, ContractError> {
+    Ok(contract::migrate(deps, env, msg)?.change_custom().unwrap())
+}
+
+fn trader_contract() -> Box<dyn Contract<PalomaMsg, Empty>> {
+    Box::new(
+        ContractWrapper::new(contract::execute, trader_instantiate, contract::query)
+            .with_reply(contract::reply)
+            .with_migrate(trader_migrate),
+    )
+}
+
+pub struct Suite {
+    pub app: TraderApp,
+    pub recorder: PalomaRecorder,
+    pub owner: Addr,
+    pub faucet: Addr,
+    pub user: Addr,
+    pub trader: Addr,
+    pub pair: Addr,
+    pub lp_token: Addr,
+    pub router: Addr,
+    pub incentivizer: Addr,
+    pub vepadex: Addr,
+}
+
+impl Suite {
+    pub fn new() -> Self {
+        Self::with_pair_type(PairType::Xyk {})
+    }
+
+    pub fn with_pair_type(pair_type: PairType) -> Self {
+        Self::build(pair_type, false)
+    }
+
+    /// An XYK pair sending back the part of a deposit off the pool ratio.
+    pub fn with_refunding_pair() -> Self {
+        Self::build(PairType::Xyk {}, true)
+    }
+
+    fn build(pair_type: PairType, refund_excess: bool) -> Self {
+        let recorder = PalomaRecorder::default();
+        let api = MockApi::default();
+        let owner = api.addr_make("owner");
+        let faucet = api.addr_make("faucet");
+        let user = api.addr_make("user");
+        let mut app = AppBuilder::new_custom()
+            .with_custom(recorder.clone())
+            .build(|router, _, storage| {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &faucet,
+                        vec![
+                            coin(100 * POOL_RESERVE, ATOM),
+                            coin(100 * POOL_RESERVE, PADEX),
+                            coin(100 * POOL_RESERVE, USDC),
+                        ],
+                    )
+                    .unwrap();
+            });
+
+        let lp_token_code = app.store_code(mocks::cw20_token::contract());
+        let pair_code = app.store_code(mocks::pair::contract());
+        let router_code = app.store_code(mocks::router::contract());
+        let incentivizer_code = app.store_code(mocks::incentivizer::contract());
+        let vepadex_code = app.store_code(mocks::vepadex::contract());
+        let trader_code = app.store_code(trader_contract());
+
+        let lp_token = app
+            .instantiate_contract(
+                lp_token_code,
+                owner.clone(),
+                &mocks::cw20_token::InstantiateMsg {},
+                &[],
+                "lp_token",
+                None,
+            )
+            .unwrap();
+        let pair = app
+            .instantiate_contract(
+                pair_code,
+                owner.clone(),
+                &mocks::pair::InstantiateMsg {
+                    asset_infos: vec![
+                        AssetInfo::NativeToken {
+                            denom: ATOM.to_string(),
+                        },
+                        AssetInfo::NativeToken {
+                            denom: USDC.to_string(),
+                        },
+                    ],
+                    liquidity_token: lp_token.clone(),
+                    pair_type,
+                    refund_excess,
+                },
+                &[],
+                "pair",
+                None,
+            )
+            .unwrap();
+        let router = app
+            .instantiate_contract(
+                router_code,
+                owner.clone(),
+                &mocks::router::InstantiateMsg {
+                    rate: Decimal::from_atomics(ROUTER_RATE, 0).unwrap(),
+                    reported_rate: None,
+                },
+                &[],
+                "router",
+                None,
+            )
+            .unwrap();
+        let incentivizer = app
+            .instantiate_contract(
+                incentivizer_code,
+                owner.clone(),
+                &mocks::incentivizer::InstantiateMsg {
+                    reward: coin(PADEX_REWARD, PADEX),
+                },
+                &[],
+                "incentivizer",
+                None,
+            )
+            .unwrap();
+        let vepadex = app
+            .instantiate_contract(
+                vepadex_code,
+                owner.clone(),
+                &mocks::vepadex::InstantiateMsg {
+                    denom: PADEX.to_string(),
+                },
+                &[],
+                "vepadex",
+                None,
+            )
+            .unwrap();
+        let trader = app
+            .instantiate_contract(
+                trader_code,
+                owner.clone(),
+                &InstantiateMsg {
+                    retry_delay: 60,
+                    owners: vec![owner.to_string()],
+                    incentivizer: incentivizer.clone(),
+                    padex: PADEX.to_string(),
+                    vepades: VEPADEX.to_string(),
+                    approval_threshold: 1,
+                    proposal_ttl: PROPOSAL_TTL,
+                },
+                &[],
+                "trader",
+                Some(owner.to_string()),
+            )
+            .unwrap();
+
+        // Seed the pool and fund the payout accounts of the mocks.
+        app.execute_contract(
+            faucet.clone(),
+            pair.clone(),
+            &mocks::pair::ExecuteMsg::ProvideLiquidity {
+                assets: vec![],
+                slippage_tolerance: None,
+                receiver: None,
+            },
+            &[coin(POOL_RESERVE, ATOM), coin(POOL_RESERVE, USDC)],
+        )
+        .unwrap();
+        app.send_tokens(faucet.clone(), router.clone(), &coins(POOL_RESERVE, USDC))
+            .unwrap();
+        app.send_tokens(
+            faucet.clone(),
+            incentivizer.clone(),
+            &coins(POOL_RESERVE, PADEX),
+        )
+        .unwrap();
+
+        Suite {
+            app,
+            recorder,
+            owner,
+            faucet,
+            user,
+            trader,
+            pair,
+            lp_token,
+            router,
+            incentivizer,
+            vepadex,
+        }
+    }
+
+    /// Deploys another mock pair over `asset_infos` with its own LP token, without reserves.
+    pub fn add_pair(&mut self, asset_infos: Vec<AssetInfo>, pair_type: PairType) -> (Addr, Addr) {
+        let lp_token_code = self.app.store_code(mocks::cw20_token::contract());
+        let pair_code = self.app.store_code(mocks::pair::contract());
+        let lp_token = self
+            .app
+            .instantiate_contract(
+                lp_token_code,
+                self.owner.clone(),
+                &mocks::cw20_token::InstantiateMsg {},
+                &[],
+                "lp_token",
+                None,
+            )
+            .unwrap();
+        let pair = self
+            .app
+            .instantiate_contract(
+                pair_code,
+                self.owner.clone(),
+                &mocks::pair::InstantiateMsg {
+                    asset_infos,
+                    liquidity_token: lp_token.clone(),
+                    pair_type,
+                    refund_excess: false,
+                },
+                &[],
+                "pair",
+                None,
+            )
+            .unwrap();
+        (pair, lp_token)
+    }
+
+    pub fn execute(&mut self, sender: &Addr, msg: ExecuteMsg) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(sender.clone(), self.trader.clone(), &msg, &[])
+    }
+
+    pub fn execute_as_owner(&mut self, msg: ExecuteMsg) -> AnyResult<AppResponse> {
+        let owner = self.owner.clone();
+        self.execute(&owner, msg)
+    }
+
+    pub fn query<T: DeserializeOwned>(&self, msg: &QueryMsg) -> T {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.trader.clone(), msg)
+            .unwrap()
+    }
+
+    /// Sends native tokens from the faucet to the trader contract.
+    pub fn fund_trader(&mut self, funds: &[Coin]) {
+        self.app
+            .send_tokens(self.faucet.clone(), self.trader.clone(), funds)
+            .unwrap();
+    }
+
+    /// Bridges `funds` in for `user`: the trader receives them and credits the ledger.
+    pub fn deposit(&mut self, user: &str, funds: &[Coin]) {
+        self.fund_trader(funds);
+        for coin in funds {
+            self.credit(user, &coin.denom, coin.amount.u128());
+        }
+    }
+
+    /// Mints LP tokens directly to the trader contract.
+    pub fn mint_lp_to_trader(&mut self, amount: u128) {
+        self.app
+            .execute_contract(
+                self.faucet.clone(),
+                self.lp_token.clone(),
+                &cw20::Cw20ExecuteMsg::Mint {
+                    recipient: self.trader.to_string(),
+                    amount: Uint128::new(amount),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    pub fn balance(&self, address: &Addr, denom: &str) -> Uint128 {
+        self.app
+            .wrap()
+            .query_balance(address, denom)
+            .unwrap()
+            .amount
+    }
+
+    pub fn lp_token_balance(&self, address: &Addr) -> Uint128 {
+        let response: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                self.lp_token.clone(),
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        response.balance
+    }
+
+    /// Internal `LP_BALANCES` entry of `user` for `token`.
+    pub fn ledger(&self, user: &str, token: &str) -> Uint128 {
+        self.query(&QueryMsg::LpQuery {
+            user: user.to_string(),
+            lp_token: token.to_string(),
+        })
+    }
+
+    /// Credits `user` with `amount` of `token` in the internal ledger.
+    pub fn credit(&mut self, user: &str, token: &str, amount: u128) {
+        self.execute_as_owner(ExecuteMsg::AddLpToken {
+            lp_token: token.to_string(),
+            user: user.to_string(),
+            amount: Uint128::new(amount),
+        })
+        .unwrap();
+    }
+
+    pub fn set_chain_setting(&mut self) {
+        self.execute_as_owner(ExecuteMsg::SetChainSetting {
+            chain_id: CHAIN_ID.to_string(),
+            compass_job_id: "compass_job".to_string(),
+            main_job_id: "main_job".to_string(),
+        })
+        .unwrap();
+    }
+
+    /// Drains the `PalomaMsg`s recorded so far.
+    pub fn take_paloma_msgs(&self) -> Vec<PalomaMsg> {
+        self.recorder.msgs.borrow_mut().drain(..).collect()
+    }
+
+    /// Drains the recorded Skyway transfers.
+    pub fn take_send_txs(&self) -> Vec<SendTx> {
+        self.take_paloma_msgs()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                PalomaMsg::SkywayMsg {
+                    send_tx: Some(send_tx),
+                    ..
+                } => Some(send_tx),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Expected Skyway transfer of `amount` to the EVM receiver.
+pub fn send_tx(amount: Coin) -> SendTx {
+    SendTx {
+        remote_chain_destination_address: EVM_RECEIVER.to_string(),
+        amount: amount.to_string(),
+        chain_reference_id: CHAIN_ID.to_string(),
+    }
+}
@@ -0,0 +1,301 @@
+use cosmwasm_std::{Addr, Uint256};
+
+use super::*;
+use crate::msg::{AdminAction, ProposalInfo};
+use crate::state::{MultisigSetting, Proposal, Role, State};
+
+/// Adds a second owner and requires both owners to approve admin actions.
+fn two_of_two(suite: &mut Suite) -> Addr {
+    let second = suite.app.api().addr_make("second_owner");
+    suite
+        .execute_as_owner(ExecuteMsg::ProposeOwner {
+            owners: vec![second.to_string()],
+        })
+        .unwrap();
+    suite
+        .execute(&second, ExecuteMsg::AcceptOwnership {})
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::UpdateApprovalThreshold {
+            approval_threshold: 2,
+        })
+        .unwrap();
+    suite.set_chain_setting();
+    second
+}
+
+/// Id of the newest pending proposal.
+fn last_proposal_id(suite: &Suite) -> u64 {
+    let proposals: Vec<ProposalInfo> = suite.query(&QueryMsg::Proposals {
+        start_after: None,
+        limit: None,
+    });
+    proposals.last().unwrap().id
+}
+
+fn set_paloma_msg() -> ExecuteMsg {
+    ExecuteMsg::SetPaloma {
+        chain_id: CHAIN_ID.to_string(),
+    }
+}
+
+#[test]
+fn admin_action_waits_for_threshold() {
+    let mut suite = Suite::new();
+    let second = two_of_two(&mut suite);
+
+    suite.execute_as_owner(set_paloma_msg()).unwrap();
+    assert!(suite.take_paloma_msgs().is_empty());
+
+    let proposals: Vec<ProposalInfo> = suite.query(&QueryMsg::Proposals {
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(
+        proposals[0].proposal.action,
+        AdminAction::SetPaloma {
+            chain_id: CHAIN_ID.to_string()
+        }
+    );
+    assert_eq!(proposals[0].proposal.approvals, vec![suite.owner.clone()]);
+
+    let proposal_id = proposals[0].id;
+    suite
+        .execute(&second, ExecuteMsg::ApproveProposal { proposal_id })
+        .unwrap();
+    assert_eq!(suite.take_paloma_msgs().len(), 1);
+    let proposals: Vec<ProposalInfo> = suite.query(&QueryMsg::Proposals {
+        start_after: None,
+        limit: None,
+    });
+    assert!(proposals.is_empty());
+}
+
+#[test]
+fn approve_rejects_duplicates_and_strangers() {
+    let mut suite = Suite::new();
+    two_of_two(&mut suite);
+    suite.execute_as_owner(set_paloma_msg()).unwrap();
+    let proposal_id = last_proposal_id(&suite);
+
+    let err = suite
+        .execute_as_owner(ExecuteMsg::ApproveProposal { proposal_id })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::AlreadyApproved {})
+    ));
+    let user = suite.user.clone();
+    let err = suite
+        .execute(&user, ExecuteMsg::ApproveProposal { proposal_id })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+    let err = suite
+        .execute_as_owner(ExecuteMsg::ApproveProposal {
+            proposal_id: proposal_id + 1,
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::ProposalNotFound {})
+    ));
+}
+
+#[test]
+fn expired_proposal_cannot_be_approved() {
+    let mut suite = Suite::new();
+    let second = two_of_two(&mut suite);
+    suite.execute_as_owner(set_paloma_msg()).unwrap();
+    let proposal_id = last_proposal_id(&suite);
+
+    suite
+        .app
+        .update_block(|block| block.time = block.time.plus_seconds(PROPOSAL_TTL));
+    let proposals: Vec<ProposalInfo> = suite.query(&QueryMsg::Proposals {
+        start_after: None,
+        limit: None,
+    });
+    assert!(proposals.is_empty());
+    let err = suite
+        .execute(&second, ExecuteMsg::ApproveProposal { proposal_id })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::ProposalExpired {})
+    ));
+    assert!(suite.take_paloma_msgs().is_empty());
+}
+
+#[test]
+fn fee_manager_proposal_needs_owner_approval() {
+    let mut suite = Suite::new();
+    suite.set_chain_setting();
+    let fee_manager = suite.app.api().addr_make("fee_manager");
+    suite
+        .execute_as_owner(ExecuteMsg::GrantRole {
+            role: Role::FeeManager,
+            address: fee_manager.to_string(),
+        })
+        .unwrap();
+
+    suite
+        .execute(
+            &fee_manager,
+            ExecuteMsg::UpdateGasFee {
+                chain_id: CHAIN_ID.to_string(),
+                new_gas_fee: Uint256::one(),
+            },
+        )
+        .unwrap();
+    assert!(suite.take_paloma_msgs().is_empty());
+    let proposal: Proposal = suite.query(&QueryMsg::Proposal { proposal_id: 1 });
+    assert_eq!(proposal.proposer, fee_manager);
+    assert!(proposal.approvals.is_empty());
+
+    suite
+        .execute_as_owner(ExecuteMsg::ApproveProposal { proposal_id: 1 })
+        .unwrap();
+    assert_eq!(suite.take_paloma_msgs().len(), 1);
+}
+
+#[test]
+fn threshold_is_bounded_by_owners() {
+    let mut suite = Suite::new();
+    let err = suite
+        .execute_as_owner(ExecuteMsg::UpdateApprovalThreshold {
+            approval_threshold: 2,
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidThreshold {})
+    ));
+
+    let second = two_of_two(&mut suite);
+    let err = suite
+        .execute_as_owner(ExecuteMsg::RemoveOwner {
+            owner: second.to_string(),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidThreshold {})
+    ));
+    let setting: MultisigSetting = suite.query(&QueryMsg::MultisigSetting {});
+    assert_eq!(setting.approval_threshold, 2);
+    assert_eq!(setting.proposal_ttl, PROPOSAL_TTL);
+}
+
+#[test]
+fn owner_removal_needs_approval() {
+    let mut suite = Suite::new();
+    let second = two_of_two(&mut suite);
+    let third = suite.app.api().addr_make("third_owner");
+    suite
+        .execute_as_owner(ExecuteMsg::ProposeOwner {
+            owners: vec![third.to_string()],
+        })
+        .unwrap();
+    let proposal_id = last_proposal_id(&suite);
+    suite
+        .execute(&second, ExecuteMsg::ApproveProposal { proposal_id })
+        .unwrap();
+    suite
+        .execute(&third, ExecuteMsg::AcceptOwnership {})
+        .unwrap();
+
+    // With 2-of-3 one owner alone cannot drop a co-signer.
+    suite
+        .execute_as_owner(ExecuteMsg::RemoveOwner {
+            owner: second.to_string(),
+        })
+        .unwrap();
+    let state: State = suite.query(&QueryMsg::State {});
+    assert_eq!(
+        state.owners,
+        vec![suite.owner.clone(), second.clone(), third.clone()]
+    );
+    let proposal_id = last_proposal_id(&suite);
+    suite
+        .execute(&third, ExecuteMsg::ApproveProposal { proposal_id })
+        .unwrap();
+    let state: State = suite.query(&QueryMsg::State {});
+    assert_eq!(state.owners, vec![suite.owner.clone(), third]);
+}
+
+#[test]
+fn proposal_ttl_must_be_positive() {
+    let mut suite = Suite::new();
+    let err = suite
+        .execute_as_owner(ExecuteMsg::UpdateConfig {
+            retry_delay: None,
+            proposal_ttl: Some(0),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidProposalTtl {})
+    ));
+    let setting: MultisigSetting = suite.query(&QueryMsg::MultisigSetting {});
+    assert_eq!(setting.proposal_ttl, PROPOSAL_TTL);
+}
+
+#[test]
+fn threshold_and_owner_changes_need_approval() {
+    let mut suite = Suite::new();
+    let second = two_of_two(&mut suite);
+    let third = suite.app.api().addr_make("third_owner");
+
+    suite
+        .execute_as_owner(ExecuteMsg::UpdateApprovalThreshold {
+            approval_threshold: 1,
+        })
+        .unwrap();
+    let threshold_proposal = last_proposal_id(&suite);
+    suite
+        .execute_as_owner(ExecuteMsg::ProposeOwner {
+            owners: vec![third.to_string()],
+        })
+        .unwrap();
+    let owner_proposal = last_proposal_id(&suite);
+    let setting: MultisigSetting = suite.query(&QueryMsg::MultisigSetting {});
+    assert_eq!(setting.approval_threshold, 2);
+    let pending: Vec<Addr> = suite.query(&QueryMsg::PendingOwners {});
+    assert!(pending.is_empty());
+    let err = suite
+        .execute(&third, ExecuteMsg::AcceptOwnership {})
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    suite
+        .execute(
+            &second,
+            ExecuteMsg::ApproveProposal {
+                proposal_id: threshold_proposal,
+            },
+        )
+        .unwrap();
+    let setting: MultisigSetting = suite.query(&QueryMsg::MultisigSetting {});
+    assert_eq!(setting.approval_threshold, 1);
+    suite
+        .execute(
+            &second,
+            ExecuteMsg::ApproveProposal {
+                proposal_id: owner_proposal,
+            },
+        )
+        .unwrap();
+    suite
+        .execute(&third, ExecuteMsg::AcceptOwnership {})
+        .unwrap();
+    let state: State = suite.query(&QueryMsg::State {});
+    assert_eq!(state.owners, vec![suite.owner.clone(), second, third]);
+    assert!(suite.take_paloma_msgs().is_empty());
+}
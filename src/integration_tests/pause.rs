@@ -0,0 +1,149 @@
+use cosmwasm_std::{coin, Uint128};
+
+use super::*;
+use crate::msg::SwapOperation;
+use crate::state::{PauseCategory, PauseFlags, Role};
+
+fn send_to_evm_msg() -> ExecuteMsg {
+    ExecuteMsg::SendToEVM {
+        chain_id: CHAIN_ID.to_string(),
+        amounts: vec!["100uatom".to_string()],
+        recipient: EVM_RECEIVER.to_string(),
+    }
+}
+
+fn assert_paused(res: AnyResult<AppResponse>) {
+    let err = res.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Paused {})
+    ));
+}
+
+#[test]
+fn pauser_blocks_single_category() {
+    let mut suite = Suite::new();
+    suite.deposit(EVM_RECEIVER, &[coin(1_000, ATOM)]);
+    let pauser = suite.app.api().addr_make("pauser");
+    suite
+        .execute_as_owner(ExecuteMsg::GrantRole {
+            role: Role::Pauser,
+            address: pauser.to_string(),
+        })
+        .unwrap();
+
+    suite
+        .execute(
+            &pauser,
+            ExecuteMsg::Pause {
+                category: PauseCategory::Bridging,
+            },
+        )
+        .unwrap();
+    let flags: PauseFlags = suite.query(&QueryMsg::PauseFlags {});
+    assert!(flags.bridging && !flags.all && !flags.swaps);
+
+    assert_paused(suite.execute_as_owner(send_to_evm_msg()));
+    // Exchange bridges its output, so it is blocked as well.
+    assert_paused(suite.execute_as_owner(ExecuteMsg::Exchange {
+        dex_router: suite.router.clone(),
+        operations: vec![SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: ATOM.to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: USDC.to_string(),
+            },
+        }],
+        minimum_receive: None,
+        max_spread: None,
+        funds: vec![coin(1_000, ATOM)],
+        offer_amount: None,
+        chain_id: CHAIN_ID.to_string(),
+        recipient: EVM_RECEIVER.to_string(),
+        refund_on_failure: false,
+        delivery: Delivery::Bridge,
+    }));
+    // Other categories and recovery calls keep working.
+    suite
+        .execute_as_owner(ExecuteMsg::AddLiquidity {
+            pair: suite.pair.clone(),
+            coins: vec![coin(1_000, ATOM)],
+            slippage_tolerance: None,
+            depositor: EVM_RECEIVER.to_string(),
+            refund_on_failure: false,
+            min_lp_out: None,
+        })
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::CancelTx { transaction_id: 1 })
+        .unwrap();
+
+    suite
+        .execute(
+            &pauser,
+            ExecuteMsg::Unpause {
+                category: PauseCategory::Bridging,
+            },
+        )
+        .unwrap();
+    suite.deposit(EVM_RECEIVER, &[coin(100, ATOM)]);
+    suite.execute_as_owner(send_to_evm_msg()).unwrap();
+}
+
+#[test]
+fn pause_all_blocks_every_category() {
+    let mut suite = Suite::new();
+    suite
+        .execute_as_owner(ExecuteMsg::Pause {
+            category: PauseCategory::All,
+        })
+        .unwrap();
+
+    assert_paused(suite.execute_as_owner(send_to_evm_msg()));
+    let user = suite.user.clone();
+    assert_paused(suite.execute_as_owner(ExecuteMsg::Unlock {
+        vepadex: suite.vepadex.clone(),
+        user: user.to_string(),
+    }));
+    assert_paused(suite.execute_as_owner(ExecuteMsg::ClaimRewards {
+        incentivizer: suite.incentivizer.clone(),
+        tokens: vec![suite.lp_token.to_string()],
+        recipient: user.to_string(),
+    }));
+    // Admin calls are never paused.
+    suite.mint_lp_to_trader(1);
+    suite
+        .execute_as_owner(ExecuteMsg::AddLpToken {
+            lp_token: suite.lp_token.to_string(),
+            user: user.to_string(),
+            amount: Uint128::one(),
+        })
+        .unwrap();
+    suite
+        .execute_as_owner(ExecuteMsg::UpdateConfig {
+            retry_delay: Some(1),
+            proposal_ttl: None,
+        })
+        .unwrap();
+}
+
+#[test]
+fn only_pausers_can_pause() {
+    let mut suite = Suite::new();
+    let user = suite.user.clone();
+    let err = suite
+        .execute(
+            &user,
+            ExecuteMsg::Pause {
+                category: PauseCategory::All,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+    let flags: PauseFlags = suite.query(&QueryMsg::PauseFlags {});
+    assert_eq!(flags, PauseFlags::default());
+}
@@ -1,5 +1,8 @@
+mod auth;
 pub mod contract;
 mod error;
+#[cfg(test)]
+mod integration_tests;
 pub mod msg;
 pub mod state;
 
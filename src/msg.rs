@@ -1,7 +1,12 @@
 #[allow(unused_imports)]
-use crate::state::{ChainSetting, State};
+use crate::state::{
+    ChainSetting, MultisigSetting, PauseCategory, PauseFlags, PriceGuard, Proposal, Role, State,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Binary, Coin, CustomMsg, Decimal, Uint128, Uint256};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -10,6 +15,8 @@ pub struct InstantiateMsg {
     pub incentivizer: Addr,
     pub padex: String,
     pub vepades: String,
+    pub approval_threshold: u64,
+    pub proposal_ttl: u64,
 }
 
 #[cw_serde]
@@ -17,6 +24,8 @@ pub struct MigrateMsg {
     pub incentivizer: Addr,
     pub padex: String,
     pub vepades: String,
+    pub approval_threshold: u64,
+    pub proposal_ttl: u64,
 }
 
 #[cw_serde]
@@ -26,9 +35,20 @@ pub enum ExecuteMsg {
         operations: Vec<SwapOperation>,
         minimum_receive: Option<Uint128>,
         max_spread: Option<Decimal>,
+        /// A single coin of the first hop's offer asset; leave empty when using `offer_amount`
+        #[serde(default)]
         funds: Vec<Coin>,
+        /// Amount of the first hop's offer asset, instead of `funds`
+        offer_amount: Option<Uint128>,
         chain_id: String,
         recipient: String,
+        /// Credit the offer to `recipient`'s ledger balance instead of reverting when the
+        /// swap fails
+        #[serde(default)]
+        refund_on_failure: bool,
+        /// Where native output goes; `chain_id` only matters when bridging
+        #[serde(default)]
+        delivery: Delivery,
     },
     SendToken {
         chain_id: String,
@@ -42,12 +62,28 @@ pub enum ExecuteMsg {
         coins: Vec<Coin>,
         slippage_tolerance: Option<Decimal>,
         depositor: String,
+        /// Credit the coins to `depositor`'s ledger balance instead of reverting when the
+        /// zap swap or the provision fails
+        #[serde(default)]
+        refund_on_failure: bool,
+        /// Fail unless at least this many LP tokens are minted
+        #[serde(default)]
+        min_lp_out: Option<Uint128>,
     },
     RemoveLiquidity {
         chain_id: String,
         pair: Addr,
         amount: Uint128,
         receiver: String,
+        /// Fail unless at least these amounts are withdrawn, checked before bridging
+        #[serde(default)]
+        min_assets_out: Vec<Coin>,
+        /// Swap the other withdrawn asset into this pool denom and deliver a single coin
+        #[serde(default)]
+        receive_asset: Option<String>,
+        /// Where the withdrawn coins go; `chain_id` only matters when bridging
+        #[serde(default)]
+        delivery: Delivery,
     },
     SendToEVM {
         chain_id: String,
@@ -120,28 +156,170 @@ pub enum ExecuteMsg {
         chain_id: String,
         new_service_fee: Uint256,
     },
+    /// Sets or, with `None`, removes the price guard of a pair
+    SetPriceGuard {
+        pair: Addr,
+        guard: Option<PriceGuard>,
+    },
+    /// Adds the sender's approval to a pending admin action
+    ApproveProposal {
+        proposal_id: u64,
+    },
     UpdateConfig {
         retry_delay: Option<u64>,
+        proposal_ttl: Option<u64>,
     },
-    AddOwner {
+    /// Proposes a new approval threshold, applied once enough owners approve it
+    UpdateApprovalThreshold {
+        approval_threshold: u64,
+    },
+    /// Proposes new owners; once enough owners approve, each becomes an owner by sending
+    /// `AcceptOwnership`
+    ProposeOwner {
         owners: Vec<String>,
     },
+    AcceptOwnership {},
+    CancelOwnerProposal {
+        owner: String,
+    },
+    /// Proposes removing an owner, applied once enough owners approve it
     RemoveOwner {
         owner: String,
     },
+    GrantRole {
+        role: Role,
+        address: String,
+    },
+    RevokeRole {
+        role: Role,
+        address: String,
+    },
+    /// Blocks a category of user-facing operations; admin calls and `CancelTx` stay available
+    Pause {
+        category: PauseCategory,
+    },
+    Unpause {
+        category: PauseCategory,
+    },
     CancelTx {
         transaction_id: u64,
     },
+    /// Internal steps of a route executed hop by hop, only callable by the contract itself
+    Callback(CallbackMsg),
+}
+
+#[cw_serde]
+pub enum CallbackMsg {
+    /// Swaps what the previous hop produced (balance of `prev_balance.info` above the
+    /// snapshot) through the next operation, or checks `minimum_receive` once no hops remain
+    ContinueRoute {
+        dex_router: Addr,
+        operations: Vec<SwapOperation>,
+        prev_balance: Asset,
+        /// Floor on what the previous hop produced, set by its pair's price guard
+        hop_minimum: Option<Uint128>,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+    },
+}
+
+/// How output coins reach their owner
+#[cw_serde]
+#[derive(Default)]
+pub enum Delivery {
+    /// Bridge to the EVM address on `chain_id` through Skyway
+    #[default]
+    Bridge,
+    /// Send to a Paloma address
+    Send { address: String },
+    /// Credit the owner's ledger balance, e.g. to fund a later `AddLiquidity`
+    Credit,
+}
+
+/// Admin action held as a proposal until enough owners approve it
+#[cw_serde]
+pub enum AdminAction {
+    SetPaloma {
+        chain_id: String,
+    },
+    UpdateRefundWallet {
+        chain_id: String,
+        new_refund_wallet: String,
+    },
+    UpdateGasFee {
+        chain_id: String,
+        new_gas_fee: Uint256,
+    },
+    UpdateServiceFeeCollector {
+        chain_id: String,
+        new_service_fee_collector: String,
+    },
+    UpdateServiceFee {
+        chain_id: String,
+        new_service_fee: Uint256,
+    },
+    UpdateApprovalThreshold {
+        approval_threshold: u64,
+    },
+    ProposeOwner {
+        owners: Vec<String>,
+    },
+    RemoveOwner {
+        owner: String,
+    },
+}
+
+impl AdminAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AdminAction::SetPaloma { .. } => "set_paloma",
+            AdminAction::UpdateRefundWallet { .. } => "update_refund_wallet",
+            AdminAction::UpdateGasFee { .. } => "update_gas_fee",
+            AdminAction::UpdateServiceFeeCollector { .. } => "update_service_fee_collector",
+            AdminAction::UpdateServiceFee { .. } => "update_service_fee",
+            AdminAction::UpdateApprovalThreshold { .. } => "update_approval_threshold",
+            AdminAction::ProposeOwner { .. } => "propose_owner",
+            AdminAction::RemoveOwner { .. } => "remove_owner",
+        }
+    }
 }
 
 #[cw_serde]
 pub enum SwapOperation {
+    /// Swap executed by the router; token factory denoms are `NativeToken` infos like any
+    /// other bank denom
     AstroSwap {
         /// Information about the asset being swapped
         offer_asset_info: AssetInfo,
         /// Information about the asset we swap to
         ask_asset_info: AssetInfo,
     },
+    /// Swap sent straight to `pair`, for hops the router does not know about
+    PairSwap {
+        pair: Addr,
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
+}
+
+impl SwapOperation {
+    pub fn offer_asset_info(&self) -> AssetInfo {
+        match self {
+            SwapOperation::AstroSwap {
+                offer_asset_info, ..
+            }
+            | SwapOperation::PairSwap {
+                offer_asset_info, ..
+            } => offer_asset_info.clone(),
+        }
+    }
+
+    pub fn ask_asset_info(&self) -> AssetInfo {
+        match self {
+            SwapOperation::AstroSwap { ask_asset_info, .. }
+            | SwapOperation::PairSwap { ask_asset_info, .. } => ask_asset_info.clone(),
+        }
+    }
 }
 
 #[cw_serde]
@@ -153,6 +331,15 @@ pub enum AssetInfo {
     NativeToken { denom: String },
 }
 
+impl fmt::Display for AssetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetInfo::NativeToken { denom } => write!(f, "{denom}"),
+            AssetInfo::Token { contract_addr } => write!(f, "{contract_addr}"),
+        }
+    }
+}
+
 #[cw_serde]
 pub enum ExternalExecuteMsg {
     ExecuteSwapOperations {
@@ -193,6 +380,12 @@ pub enum IncentivizerExecuteMsg {
     },
 }
 
+#[cw_serde]
+pub enum IncentivizerQueryMsg {
+    /// Amount of `lp_token` staked on behalf of `user`
+    Deposit { lp_token: String, user: String },
+}
+
 #[cw_serde]
 pub enum VePadexExecuteMsg {
     CreateLock {
@@ -225,6 +418,13 @@ pub struct Asset {
     pub info: AssetInfo,
     pub amount: Uint128,
 }
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.info)
+    }
+}
+
 #[cw_serde]
 pub enum PalomaMsg {
     /// Message struct for cross-chain calls.
@@ -265,6 +465,153 @@ pub enum QueryMsg {
 
     #[returns(Uint128)]
     LpQuery { user: String, lp_token: String },
+    /// Query every ledger balance of a user, ordered by token
+    #[returns(Vec<LpBalanceInfo>)]
+    LpBalances {
+        user: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Query every holder of a token, ordered by user
+    #[returns(Vec<LpBalanceInfo>)]
+    LpHolders {
+        lp_token: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Compare the ledger total of a token with what the contract actually holds; the
+    /// incentivizer stake is summed over one page of holders
+    #[returns(ReconcileResponse)]
+    Reconcile {
+        lp_token: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Query the sum of ledger balances per token
+    #[returns(Vec<LpTotalInfo>)]
+    LpTotals {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Query the multisig threshold and proposal lifetime
+    #[returns(MultisigSetting)]
+    MultisigSetting {},
+    /// Query a pending admin proposal with its approvals
+    #[returns(Proposal)]
+    Proposal { proposal_id: u64 },
+    /// Query unexpired admin proposals
+    #[returns(Vec<ProposalInfo>)]
+    Proposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Query the proposed owners awaiting acceptance
+    #[returns(Vec<Addr>)]
+    PendingOwners {},
+    /// Query which operation categories are paused
+    #[returns(PauseFlags)]
+    PauseFlags {},
+    /// Query the owners and every address holding a role
+    #[returns(RolesResponse)]
+    Roles {},
+    /// Query the price guard of a pair
+    #[returns(Option<PriceGuard>)]
+    PriceGuard { pair: Addr },
+    /// Simulate `Exchange` of `offer_amount` of the first offer asset along `operations`
+    #[returns(SimulateExchangeResponse)]
+    SimulateExchange {
+        dex_router: Addr,
+        operations: Vec<SwapOperation>,
+        offer_amount: Uint128,
+    },
+    /// Simulate `AddLiquidity`, including the single-sided zap
+    #[returns(SimulateAddLiquidityResponse)]
+    SimulateAddLiquidity {
+        pair: Addr,
+        coins: Vec<Coin>,
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Simulate the assets returned for burning `amount` LP tokens of `pair`
+    #[returns(Vec<Asset>)]
+    SimulateRemoveLiquidity { pair: Addr, amount: Uint128 },
+}
+
+#[cw_serde]
+pub struct ProposalInfo {
+    pub id: u64,
+    pub proposal: Proposal,
+}
+
+#[cw_serde]
+pub struct RoleInfo {
+    pub address: Addr,
+    pub roles: Vec<Role>,
+}
+
+#[cw_serde]
+pub struct LpBalanceInfo {
+    pub user: String,
+    pub lp_token: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct LpTotalInfo {
+    pub lp_token: String,
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct ReconcileResponse {
+    /// Sum of LP_BALANCES for the token
+    pub internal_total: Uint128,
+    /// Token balance held by the contract (cw20 or native)
+    pub contract_balance: Uint128,
+    /// Staked in the incentivizer on behalf of the page's ledger holders. Deposits debit the
+    /// ledger, so this is reported for context and not counted against `internal_total`.
+    pub staked_balance: Uint128,
+    /// Last holder of the page, the `start_after` of the next one; `None` once exhausted
+    pub last_holder: Option<String>,
+    /// `contract_balance - internal_total` when the contract holds more than it owes
+    pub surplus: Uint128,
+    /// `internal_total - contract_balance` when the ledger is not covered
+    pub deficit: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateExchangeResponse {
+    /// Expected output of the last hop
+    pub return_amount: Uint128,
+    /// One entry per swap the contract would send, in order
+    pub swaps: Vec<SwapSimulation>,
+}
+
+#[cw_serde]
+pub struct SwapSimulation {
+    pub operations: Vec<SwapOperation>,
+    pub offer_amount: Uint128,
+    pub return_amount: Uint128,
+    /// Pool fee and spread in the ask asset. Only pairs report these; the router
+    /// answers with the return amount alone.
+    pub commission_amount: Option<Uint128>,
+    pub spread_amount: Option<Uint128>,
+    /// `spread_amount` relative to the output before spread and fees
+    pub price_impact: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct SimulateAddLiquidityResponse {
+    /// Input swapped into the other asset by the single-sided zap, if any
+    pub swap: Option<SwapSimulation>,
+    /// LP tokens minted for the depositor
+    pub lp_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    /// Owners, who implicitly hold every role
+    pub owners: Vec<Addr>,
+    pub members: Vec<RoleInfo>,
 }
 
 #[cw_serde]
@@ -281,6 +628,56 @@ pub enum ExternalQueryMsg {
         /// The pair type for which we return fee information. Pair type is a [`PairType`] struct
         pair_type: PairType,
     },
+    /// Pair: accumulated prices as of `ConfigResponse.block_time_last`
+    #[returns(CumulativePricesResponse)]
+    CumulativePrices {},
+    /// Pair: simulate swapping `offer_asset`
+    #[returns(SimulationResponse)]
+    Simulation {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+    },
+    /// Pair: LP tokens minted for providing `assets`
+    #[returns(Uint128)]
+    SimulateProvide {
+        assets: Vec<Asset>,
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Pair: assets returned for burning `lp_amount`
+    #[returns(Vec<Asset>)]
+    SimulateWithdraw { lp_amount: Uint128 },
+    /// Router: simulate a multi-hop swap
+    #[returns(SimulateSwapOperationsResponse)]
+    SimulateSwapOperations {
+        offer_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
+}
+
+#[cw_serde]
+pub struct CumulativePricesResponse {
+    /// The assets in the pool to query
+    pub assets: Vec<Asset>,
+    /// The total amount of LP tokens currently issued
+    pub total_share: Uint128,
+    /// `(offer, ask, price × seconds)` with prices scaled by 10^`TWAP_PRECISION`
+    pub cumulative_prices: Vec<(AssetInfo, AssetInfo, Uint128)>,
+}
+
+#[cw_serde]
+pub struct SimulationResponse {
+    /// The amount of ask assets returned by the swap
+    pub return_amount: Uint128,
+    /// The spread used in the swap operation
+    pub spread_amount: Uint128,
+    /// The amount of fees charged by the transaction
+    pub commission_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateSwapOperationsResponse {
+    /// The amount of tokens received in a swap simulation
+    pub amount: Uint128,
 }
 
 /// This structure stores the main parameters for an palomadex pair
@@ -327,6 +724,14 @@ pub struct ConfigResponse {
     pub factory_addr: Addr,
 }
 
+/// `ConfigResponse.params` of a stable pair. Not `cw_serde`, so fields added by newer
+/// pairs (e.g. `fee_share`) are ignored.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StablePoolConfig {
+    /// The stableswap amplification
+    pub amp: Decimal,
+}
+
 #[cw_serde]
 pub struct FeeInfoResponse {
     /// Contract address to send governance fees to
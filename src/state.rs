@@ -2,8 +2,11 @@ use cosmwasm_schema::cw_serde;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Timestamp, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Decimal, Empty, StdError, StdResult, Storage, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, KeyDeserialize, Map, MultiIndex};
+
+use crate::msg::{AdminAction, AssetInfo};
+use crate::ContractError;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct State {
@@ -17,6 +20,104 @@ pub struct ChainSetting {
     pub main_job_id: String,
 }
 
+/// Permissions granted on top of ownership. Owners act as admins and pass every role check.
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum Role {
+    /// Relayer running swaps, liquidity, incentives and bridging
+    Operator,
+    /// Updates gas and service fees on the remote chains
+    FeeManager,
+    /// Pauses and unpauses the contract
+    Pauser,
+}
+
+/// Groups of user-facing operations that can be paused independently.
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum PauseCategory {
+    /// Every category below
+    All,
+    Swaps,
+    Liquidity,
+    Incentives,
+    VePadex,
+    /// Anything emitting a `SkywayMsg` to a remote chain
+    Bridging,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseFlags {
+    pub all: bool,
+    pub swaps: bool,
+    pub liquidity: bool,
+    pub incentives: bool,
+    pub vepadex: bool,
+    pub bridging: bool,
+}
+
+impl PauseFlags {
+    pub fn set(&mut self, category: PauseCategory, paused: bool) {
+        match category {
+            PauseCategory::All => self.all = paused,
+            PauseCategory::Swaps => self.swaps = paused,
+            PauseCategory::Liquidity => self.liquidity = paused,
+            PauseCategory::Incentives => self.incentives = paused,
+            PauseCategory::VePadex => self.vepadex = paused,
+            PauseCategory::Bridging => self.bridging = paused,
+        }
+    }
+
+    pub fn is_paused(&self, category: PauseCategory) -> bool {
+        self.all
+            || match category {
+                PauseCategory::All => false,
+                PauseCategory::Swaps => self.swaps,
+                PauseCategory::Liquidity => self.liquidity,
+                PauseCategory::Incentives => self.incentives,
+                PauseCategory::VePadex => self.vepadex,
+                PauseCategory::Bridging => self.bridging,
+            }
+    }
+}
+
+#[cw_serde]
+pub struct MultisigSetting {
+    /// Owner approvals needed before a proposal is dispatched
+    pub approval_threshold: u64,
+    /// Seconds a proposal stays open
+    pub proposal_ttl: u64,
+}
+
+#[cw_serde]
+pub struct Proposal {
+    pub action: AdminAction,
+    pub proposer: Addr,
+    pub approvals: Vec<Addr>,
+    pub expires_at: Timestamp,
+}
+
+/// Price protection for swaps through a pair when the caller sets no `minimum_receive`
+#[cw_serde]
+pub struct PriceGuard {
+    /// Largest shortfall of the realized rate below the expected price, pool fees included
+    pub max_deviation: Decimal,
+    /// Admin-set `(offer, ask, price)` used instead of the TWAP for that direction
+    #[serde(default)]
+    pub reference_prices: Vec<(AssetInfo, AssetInfo, Decimal)>,
+}
+
+/// Last cumulative prices read from a guarded pair
+#[cw_serde]
+pub struct PriceObservation {
+    pub cumulative_prices: Vec<(AssetInfo, AssetInfo, Uint128)>,
+    /// The pair's `block_time_last` when the cumulative prices were read
+    pub block_time_last: u64,
+    /// `(offer, ask, price)` averaged between the two latest observations
+    pub twap: Vec<(AssetInfo, AssetInfo, Decimal)>,
+}
+
 #[cw_serde]
 pub struct IncentivesSetting {
     pub incentivizer: Addr,
@@ -24,8 +125,98 @@ pub struct IncentivesSetting {
     pub vepades: String,
 }
 
+pub struct LpBalanceIndexes<'a> {
+    pub token: MultiIndex<'a, String, Uint128, (String, String)>,
+}
+
+impl IndexList<Uint128> for LpBalanceIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Uint128>> + '_> {
+        let v: Vec<&dyn Index<Uint128>> = vec![&self.token];
+        Box::new(v.into_iter())
+    }
+}
+
+fn lp_balance_token(pk: &[u8], _: &Uint128) -> String {
+    // primary keys are always written by LP_BALANCES itself
+    <(String, String)>::from_slice(pk)
+        .expect("invalid lp_balances key")
+        .1
+}
+
 pub const CHAIN_SETTINGS: Map<String, ChainSetting> = Map::new("chain_settings");
 pub const STATE: Item<State> = Item::new("state");
-pub const LP_BALANCES: Map<(String, String), Uint128> = Map::new("lp_balances");
+pub const ROLES: Map<Addr, Vec<Role>> = Map::new("roles");
+/// Proposed owners awaiting `AcceptOwnership`, mapped to the owner who proposed them
+pub const PENDING_OWNERS: Map<Addr, Addr> = Map::new("pending_owners");
+/// Ledger balances keyed by (user, token), indexed by token to list holders
+pub const LP_BALANCES: IndexedMap<(String, String), Uint128, LpBalanceIndexes> = IndexedMap::new(
+    "lp_balances",
+    LpBalanceIndexes {
+        token: MultiIndex::new(lp_balance_token, "lp_balances", "lp_balances__token"),
+    },
+);
+/// Sum of LP_BALANCES per token, i.e. what the contract owes its users
+pub const LP_TOTALS: Map<String, Uint128> = Map::new("lp_totals");
 pub const MESSAGE_TIMESTAMP: Map<(String, String), Timestamp> = Map::new("message_timestamp");
 pub const INCENTIVES_SETTING: Item<IncentivesSetting> = Item::new("incentives_setting");
+pub const PAUSE_FLAGS: Item<PauseFlags> = Item::new("pause_flags");
+pub const MULTISIG_SETTING: Item<MultisigSetting> = Item::new("multisig_setting");
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+pub const PRICE_GUARDS: Map<Addr, PriceGuard> = Map::new("price_guards");
+pub const PRICE_OBSERVATIONS: Map<Addr, PriceObservation> = Map::new("price_observations");
+/// Guarded pairs of each `(offer, ask)` asset pair, used to floor router hops. Keyed by pair
+/// too, so guarding one pair never drops another from the index
+pub const GUARDED_PAIRS: Map<(String, String, Addr), Empty> = Map::new("guarded_pairs");
+
+/// Updates a ledger balance like `Map::update` and moves LP_TOTALS by the same delta.
+pub fn update_lp_balance<A, E>(
+    storage: &mut dyn Storage,
+    key: (String, String),
+    action: A,
+) -> Result<Uint128, E>
+where
+    A: FnOnce(Option<Uint128>) -> Result<Uint128, E>,
+    E: From<StdError>,
+{
+    let old_balance = LP_BALANCES.may_load(storage, key.clone())?;
+    let new_balance = action(old_balance)?;
+    LP_BALANCES.save(storage, key.clone(), &new_balance)?;
+    LP_TOTALS.update(storage, key.1, |total| -> StdResult<_> {
+        Ok(total
+            .unwrap_or_default()
+            .checked_add(new_balance)?
+            .checked_sub(old_balance.unwrap_or_default())?)
+    })?;
+    Ok(new_balance)
+}
+
+/// Adds `amount` of `token` to `user`'s ledger balance.
+pub fn credit_balance(
+    storage: &mut dyn Storage,
+    user: &str,
+    token: &str,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    update_lp_balance(
+        storage,
+        (user.to_string(), token.to_string()),
+        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_add(amount)?) },
+    )
+}
+
+/// Takes `amount` of `token` from `user`'s ledger balance, failing with
+/// `InsufficientBalance` when the user holds less.
+pub fn debit_balance(
+    storage: &mut dyn Storage,
+    user: &str,
+    token: &str,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    update_lp_balance(storage, (user.to_string(), token.to_string()), |balance| {
+        balance
+            .unwrap_or_default()
+            .checked_sub(amount)
+            .map_err(|_| ContractError::InsufficientBalance {})
+    })
+}